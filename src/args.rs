@@ -1,8 +1,8 @@
 use core::fmt;
 use semver::{Error as SemverError, Version};
-use std::{path::PathBuf, str::FromStr};
+use std::{num::NonZeroUsize, path::PathBuf, str::FromStr, time::Duration};
 
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Clone)]
 pub enum VersionEnum {
@@ -51,6 +51,18 @@ pub enum RuntipiMainCommand {
     Update(UpdateCommand),
     /// Manage your apps
     App(AppCommand),
+    /// Browse the app store repositories
+    Store(StoreCommand),
+    /// Manage the app store repositories
+    Repo(RepoCommand),
+    /// Manage the runtipi instances the CLI talks to
+    Context(ContextCommand),
+    /// Inspect and edit the environment of your runtipi instance
+    Env(EnvCommand),
+    /// Check the settings of your runtipi instance
+    Settings(SettingsCommand),
+    /// Show the health, version and load of your runtipi instance
+    Status,
     /// Initiate a password reset for the admin user
     ResetPassword,
     /// Debug your runtipi instance
@@ -64,6 +76,12 @@ pub enum RuntipiMainCommand {
 pub struct RuntipiArgs {
     #[clap(subcommand)]
     pub command: RuntipiMainCommand,
+    /// Timeout in seconds for each request to the dashboard API
+    #[clap(long, global = true, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+    /// Talk to this context instead of the current one, `default` is the instance in the current directory
+    #[clap(long, global = true, value_name = "NAME")]
+    pub context: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -97,49 +115,487 @@ pub struct AppCommand {
 
 #[derive(Debug, Subcommand)]
 pub enum AppSubcommand {
-    /// Start an app
+    /// Install an app
+    Install(InstallApp),
+    /// Start one or more apps
     Start(StartApp),
-    /// Stop an app
+    /// Stop one or more apps
     Stop(StopApp),
     /// Uninstall an app
     Uninstall(UninstallApp),
-    /// Reset an app
+    /// Reset one or more apps
     Reset(ResetApp),
-    /// Update an app
+    /// Update one or more apps
     Update(UpdateApp),
     /// Start all apps
     StartAll(StartAll),
+    /// List installed apps
+    List(ListApps),
+    /// Show everything known about an installed app
+    Info(AppInfo),
+    /// Manage the user config overrides of an app
+    Config(AppConfig),
+    /// Show the logs of an app
+    Logs(AppLogs),
+    /// Run a command in a container of an app
+    Exec(AppExec),
+    /// Open an interactive shell in a container of an app
+    Shell(AppShell),
+    /// Create a backup of an app
+    Backup(BackupApp),
+    /// List the backups of an app
+    Backups(ListBackups),
+    /// Restore an app from one of its backups
+    Restore(RestoreApp),
 }
 
 #[derive(Debug, Args)]
-pub struct StartApp {
-    /// The id of the app to start
+pub struct WaitArgs {
+    /// Wait until the app reaches its target state instead of returning once the request is accepted
+    #[clap(long)]
+    pub wait: bool,
+    /// Maximum time to wait in seconds when using --wait [default: 300]
+    #[clap(long, requires = "wait", value_name = "SECONDS")]
+    pub wait_timeout: Option<u64>,
+}
+
+impl WaitArgs {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.wait_timeout.unwrap_or(300))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct AppSelector {
+    /// The ids of the apps
+    #[clap(required_unless_present_any = ["all", "glob", "regex"])]
+    pub ids: Vec<String>,
+    /// Select every installed app. With update, every app with a newer version in its app store
+    #[clap(long, conflicts_with_all = ["ids", "glob", "regex"])]
+    pub all: bool,
+    /// Select the installed apps matching a glob pattern eg: 'linuxserver-*'
+    #[clap(long, conflicts_with_all = ["ids", "regex"])]
+    pub glob: Option<String>,
+    /// Select the installed apps matching a regular expression
+    #[clap(long, conflicts_with = "ids")]
+    pub regex: Option<String>,
+    /// How many apps to process at the same time
+    #[clap(short, long, default_value_t = 1)]
+    pub parallel: usize,
+}
+
+impl AppSelector {
+    /// Whether the apps are picked among the installed ones rather than named on the command line
+    pub fn is_selection(&self) -> bool {
+        self.all || self.glob.is_some() || self.regex.is_some()
+    }
+
+    /// Whether the command can target more than one app
+    pub fn is_bulk(&self) -> bool {
+        self.is_selection() || self.ids.len() > 1
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct InstallApp {
+    /// The id of the app to install
     pub id: String,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
+    /// Set a form field of the app eg: --set APP_PASSWORD=secret. Can be repeated
+    #[clap(long, value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+    /// Path to a JSON file with the form values of the app
+    #[clap(long)]
+    pub values: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct StartApp {
+    #[clap(flatten)]
+    pub selector: AppSelector,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct StopApp {
-    /// The id of the app to stop
-    pub id: String,
+    #[clap(flatten)]
+    pub selector: AppSelector,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct UninstallApp {
     /// The id of the app to uninstall
     pub id: String,
+    /// Also delete the backups of the app
+    #[clap(long)]
+    pub remove_backups: bool,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct ResetApp {
-    /// The id of the app to reset
-    pub id: String,
+    #[clap(flatten)]
+    pub selector: AppSelector,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
 }
 
 #[derive(Debug, Args)]
 pub struct UpdateApp {
-    /// The id of the app to update
+    #[clap(flatten)]
+    pub selector: AppSelector,
+    /// Skip the backup made before updating
+    #[clap(long)]
+    pub no_backup: bool,
+    /// Do not ask for confirmation before updating the selected apps
+    #[clap(short, long)]
+    pub yes: bool,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct StartAll {
+    /// How many apps to start at the same time, at least 1
+    #[clap(short, long, default_value = "2")]
+    pub parallel: NonZeroUsize,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ListApps {
+    /// Only show running apps
+    #[clap(long, conflicts_with = "stopped")]
+    pub running: bool,
+    /// Only show apps that are not running
+    #[clap(long)]
+    pub stopped: bool,
+    /// Only show apps with a newer version available in their app store
+    #[clap(long)]
+    pub updates_available: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AppLogs {
+    /// The id of the app to show the logs of
+    pub id: String,
+    /// Keep streaming new logs
+    #[clap(short, long)]
+    pub follow: bool,
+    /// Number of lines to show from the end of the logs of each container
+    #[clap(short = 'n', long)]
+    pub tail: Option<usize>,
+    /// Show logs since a timestamp (eg: 2024-01-02T13:23:37Z) or relative duration (eg: 42m)
+    #[clap(long)]
+    pub since: Option<String>,
+    /// Only show the logs of this service. Can be repeated
+    #[clap(short, long)]
+    pub service: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct BackupApp {
+    /// The id of the app to backup
+    pub id: String,
+    /// Only keep the N most recent backups of the app once the new one is created, at least 1
+    #[clap(long, value_name = "N")]
+    pub keep: Option<NonZeroUsize>,
+    /// Maximum time to wait for the backup archive in seconds [default: 600]
+    #[clap(long, value_name = "SECONDS")]
+    pub wait_timeout: Option<u64>,
+}
+
+impl BackupApp {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.wait_timeout.unwrap_or(600))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ListBackups {
+    /// The id of the app to list the backups of
+    pub id: String,
+}
+
+#[derive(Debug, Args)]
+pub struct RestoreApp {
+    /// The id of the app to restore
+    pub id: String,
+    /// The name of the backup archive, as shown by `app backups`
+    pub archive: String,
+    #[clap(flatten)]
+    pub wait: WaitArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct AppInfo {
+    /// The id of the app
     pub id: String,
+    /// Print the information as JSON
+    #[clap(long)]
+    pub json: bool,
 }
 
 #[derive(Debug, Args)]
-pub struct StartAll {}
+pub struct AppConfig {
+    /// The id of the app
+    pub id: String,
+    #[clap(subcommand)]
+    pub command: AppConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AppConfigCommand {
+    /// Print the user config files of the app
+    Show,
+    /// Print a variable of the app.env override
+    Get {
+        /// The name of the variable
+        key: String,
+    },
+    /// Set variables in the app.env override
+    Set {
+        /// The variables to set eg: TZ=Europe/Zurich
+        #[clap(required = true, value_name = "KEY=VALUE")]
+        values: Vec<String>,
+        /// Restart the app once the configuration is saved
+        #[clap(long)]
+        restart: bool,
+    },
+    /// Remove variables from the app.env override
+    Unset {
+        /// The names of the variables
+        #[clap(required = true)]
+        keys: Vec<String>,
+        /// Restart the app once the configuration is saved
+        #[clap(long)]
+        restart: bool,
+    },
+    /// Open an override file in $EDITOR, it is validated before being saved
+    Edit {
+        /// The file to edit
+        #[clap(value_enum, default_value_t = AppConfigFile::Compose)]
+        file: AppConfigFile,
+        /// Restart the app once the configuration is saved
+        #[clap(long)]
+        restart: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AppConfigFile {
+    /// docker-compose.yml
+    Compose,
+    /// app.env
+    Env,
+}
+
+#[derive(Debug, Args)]
+pub struct AppExec {
+    /// The id of the app
+    pub id: String,
+    /// The compose service to run the command in, defaults to the main service of the app
+    #[clap(short, long)]
+    pub service: Option<String>,
+    /// The command to run eg: -- ls -la /data
+    #[clap(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct AppShell {
+    /// The id of the app
+    pub id: String,
+    /// The compose service to open the shell in, defaults to the main service of the app
+    #[clap(short, long)]
+    pub service: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct StoreCommand {
+    /// The subcommand to run
+    #[clap(subcommand)]
+    pub subcommand: StoreSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StoreSubcommand {
+    /// Search apps by name, description or category
+    Search(SearchStore),
+    /// Show the details of an app of the store
+    Show(ShowStoreApp),
+    /// List the categories of the store and how many apps they contain
+    Categories,
+}
+
+#[derive(Debug, Args)]
+pub struct SearchStore {
+    /// The term to search for eg: cloud
+    pub term: String,
+    /// Only show apps of this category
+    #[clap(short, long)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowStoreApp {
+    /// The id of the app eg: nextcloud or nextcloud:migrated
+    pub id: String,
+}
+
+#[derive(Debug, Args)]
+pub struct RepoCommand {
+    /// The subcommand to run
+    #[clap(subcommand)]
+    pub subcommand: RepoSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RepoSubcommand {
+    /// List the app store repositories with their current commit
+    List,
+    /// Add an app store repository and clone it
+    Add(AddRepo),
+    /// Remove an app store repository
+    Remove(RemoveRepo),
+    /// Pull the latest changes of the app store repositories
+    Update(UpdateRepo),
+}
+
+#[derive(Debug, Args)]
+pub struct AddRepo {
+    /// The git url of the repository eg: https://github.com/me/my-app-store
+    pub url: String,
+    /// The id of the repository, used as its folder name in repos/. Defaults to the repository name
+    #[clap(long)]
+    pub id: Option<String>,
+    /// The branch to checkout
+    #[clap(short, long)]
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveRepo {
+    /// The id of the repository
+    pub id: String,
+    /// Remove the repository even if apps are installed from it
+    #[clap(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct UpdateRepo {
+    /// The id of the repository, every repository when not set
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ContextCommand {
+    /// The subcommand to run
+    #[clap(subcommand)]
+    pub subcommand: ContextSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ContextSubcommand {
+    /// Add a remote runtipi instance
+    Add(AddContext),
+    /// Use a context for the next commands, `default` goes back to the instance in the current directory
+    Use(UseContext),
+    /// List the contexts
+    List,
+    /// Remove a context
+    Remove(RemoveContext),
+}
+
+#[derive(Debug, Args)]
+pub struct AddContext {
+    /// The name of the context eg: homelab
+    pub name: String,
+    /// The url of the dashboard API eg: https://tipi.example.com/api
+    #[clap(long)]
+    pub api_url: String,
+    /// The JWT_SECRET of the instance, used to sign a short-lived token for every request
+    #[clap(long, required_unless_present = "token", conflicts_with = "token")]
+    pub jwt_secret: Option<String>,
+    /// A token sent as is with every request
+    #[clap(long)]
+    pub token: Option<String>,
+    /// Path to a CA bundle to trust when the instance uses a self-signed certificate
+    #[clap(long)]
+    pub ca_cert: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct UseContext {
+    /// The name of the context
+    pub name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveContext {
+    /// The name of the context
+    pub name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct EnvCommand {
+    /// The subcommand to run
+    #[clap(subcommand)]
+    pub subcommand: EnvSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EnvSubcommand {
+    /// Print a variable of .env
+    Get {
+        /// The name of the variable
+        key: String,
+    },
+    /// Set variables in user-config/.env, they override the generated ones on every start
+    Set {
+        /// The variables to set eg: RUNTIPI_MEDIA_PATH=/mnt/media
+        #[clap(required = true, value_name = "KEY=VALUE")]
+        values: Vec<String>,
+    },
+    /// Remove variables from user-config/.env
+    Unset {
+        /// The names of the variables
+        #[clap(required = true)]
+        keys: Vec<String>,
+    },
+    /// List the variables of .env and where they come from
+    List {
+        /// Print passwords and secrets instead of redacting them
+        #[clap(long)]
+        show_secrets: bool,
+    },
+    /// Show the changes the next start will make to .env
+    Diff {
+        /// Print passwords and secrets instead of redacting them
+        #[clap(long)]
+        show_secrets: bool,
+        /// The custom .env file passed to start, if any
+        #[clap(short, long)]
+        env_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct SettingsCommand {
+    /// The subcommand to run
+    #[clap(subcommand)]
+    pub subcommand: SettingsSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SettingsSubcommand {
+    /// Validate state/settings.json and list every problem found
+    Validate,
+}
@@ -1,27 +1,34 @@
-use reqwest::Method;
+mod backup;
+mod batch;
+mod config;
+mod exec;
+mod info;
+mod install;
+mod list;
+mod logs;
+mod update;
+mod wait;
 
-use crate::args::{AppCommand, AppSubcommand};
+use std::env;
+
+use colored::Colorize;
+use regex::Regex;
+
+use crate::args::{AppCommand, AppSelector, AppSubcommand, StartAll, WaitArgs};
+use crate::commands::context::create_client;
 use crate::components::spinner::{self, CustomSpinner};
-use crate::utils::api::api_request;
+use crate::utils::api::{ApiError, AppLifecycleRequest, RuntipiClient};
+use crate::utils::apps::{get_installed_apps, glob_to_regex, select_app_ids};
+use crate::utils::context::{Context, DEFAULT_CONTEXT};
 use crate::utils::env::EnvMap;
+use crate::utils::schemas::UninstallAppBody;
 
-use crate::utils::constants::DEFAULT_NGINX_PORT;
+use batch::{has_failures, print_summary, run_batch, BatchAction};
+use wait::{wait_for_state, ErrorLogCursor, TargetState};
 
-fn handle_api_response(
-    spin: CustomSpinner,
-    api_response: Result<reqwest::blocking::Response, std::io::Error>,
-    success_message: &str,
-    error_message: &str,
-) {
+fn handle_api_response(spin: CustomSpinner, api_response: Result<(), ApiError>, success_message: &str, error_message: &str) {
     match api_response {
-        Ok(response) => {
-            if response.status().is_success() {
-                spin.succeed(success_message);
-            } else {
-                println!("Error code: {}", response.status());
-                spin.fail(error_message);
-            }
-        }
+        Ok(_) => spin.succeed(success_message),
         Err(err) => {
             spin.fail(error_message);
             println!("Error: {}", err);
@@ -30,51 +37,289 @@ fn handle_api_response(
     spin.finish();
 }
 
-pub fn run(args: AppCommand, env_map: EnvMap) {
-    let base_url = format!(
-        "http://{}:{}/api/app-lifecycle",
-        env_map.get("INTERNAL_IP").unwrap_or(&"localhost".to_string()),
-        env_map.get("NGINX_PORT").unwrap_or(&DEFAULT_NGINX_PORT.to_string()),
-    );
+struct LifecycleAction {
+    progress: &'static str,
+    done: &'static str,
+    target: TargetState,
+    /// The app may already be in the target state before the worker picks up the job
+    requires_transition: bool,
+}
+
+impl LifecycleAction {
+    fn of(request: &AppLifecycleRequest) -> LifecycleAction {
+        let (progress, done, target, requires_transition) = match request {
+            AppLifecycleRequest::Install(_) => ("Installing", "installed", TargetState::Running, false),
+            AppLifecycleRequest::Start => ("Starting", "started", TargetState::Running, false),
+            AppLifecycleRequest::Stop => ("Stopping", "stopped", TargetState::Stopped, false),
+            AppLifecycleRequest::Uninstall(_) => ("Uninstalling", "uninstalled", TargetState::Uninstalled, false),
+            AppLifecycleRequest::Reset => ("Resetting", "reset", TargetState::Running, true),
+            AppLifecycleRequest::Update(_) => ("Updating", "updated", TargetState::Running, true),
+            AppLifecycleRequest::Restore(_) => ("Restoring", "restored", TargetState::Running, true),
+            // Backups are tracked through the archive they produce, see `backup.rs`
+            AppLifecycleRequest::Backup => ("Backing up", "backed up", TargetState::Running, false),
+        };
+
+        LifecycleAction {
+            progress,
+            done,
+            target,
+            requires_transition,
+        }
+    }
+}
+
+/// Send a lifecycle request and, with --wait, block until the worker is done with it
+fn run_lifecycle_action(client: &RuntipiClient, request: &AppLifecycleRequest, id: &str, wait: &WaitArgs) -> Result<(), String> {
+    let action = LifecycleAction::of(request);
+    let error_log = ErrorLogCursor::new();
+    client.app_lifecycle(id, request).map_err(|e| e.to_string())?;
+
+    if wait.wait {
+        wait_for_state(client, id, action.target, action.requires_transition, wait.timeout(), &error_log)?;
+    }
+
+    Ok(())
+}
+
+fn run_single_action(client: &RuntipiClient, request: &AppLifecycleRequest, id: &str, wait: &WaitArgs) {
+    let action = LifecycleAction::of(request);
+    let spin = spinner::new(&format!("{} app {}...", action.progress, id));
+    let success_message = format!("App {} successfully!", action.done);
+
+    if !wait.wait {
+        let api_response = client.app_lifecycle(id, request);
+        // The logs of a remote instance are not on this machine
+        let error_message = match client.is_remote() {
+            true => format!("Failed to {} app {}", request.path(), id),
+            false => format!("Failed to {} app {}. See logs/error.log for more details.", request.path(), id),
+        };
+        handle_api_response(spin, api_response, &success_message, &error_message);
+        return;
+    }
+
+    spin.set_message(&format!("{} app {} and waiting for it to be {}...", action.progress, id, action.target));
+
+    match run_lifecycle_action(client, request, id, wait) {
+        Ok(_) => {
+            spin.succeed(&success_message);
+            spin.finish();
+        }
+        Err(err) => {
+            spin.fail(&format!("Failed to {} app {}", request.path(), id));
+            spin.finish();
+            println!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Subcommands reading the app folders or talking to docker can't reach a remote instance
+fn is_local_only(subcommand: &AppSubcommand) -> bool {
+    matches!(
+        subcommand,
+        AppSubcommand::Info(_)
+            | AppSubcommand::Config(_)
+            | AppSubcommand::Logs(_)
+            | AppSubcommand::Exec(_)
+            | AppSubcommand::Shell(_)
+            | AppSubcommand::Backup(_)
+            | AppSubcommand::Backups(_)
+            | AppSubcommand::Restore(_)
+    )
+}
+
+/**
+* Before the global --timeout of the API requests, `--timeout` set how long --wait and `app backup` wait. A command
+* that waits without --wait-timeout and has --timeout was likely written for the old flag, it is refused instead of
+* silently waiting for the default time.
+*/
+fn uses_old_wait_timeout(subcommand: &AppSubcommand) -> bool {
+    let wait = match subcommand {
+        AppSubcommand::Install(args) => &args.wait,
+        AppSubcommand::Start(args) => &args.wait,
+        AppSubcommand::Stop(args) => &args.wait,
+        AppSubcommand::Uninstall(args) => &args.wait,
+        AppSubcommand::Reset(args) => &args.wait,
+        AppSubcommand::Update(args) => &args.wait,
+        AppSubcommand::StartAll(args) => &args.wait,
+        AppSubcommand::Restore(args) => &args.wait,
+        AppSubcommand::Backup(args) => return args.wait_timeout.is_none(),
+        _ => return false,
+    };
+
+    wait.wait && wait.wait_timeout.is_none()
+}
+
+pub fn run(args: AppCommand, context: Option<Context>, env_map: EnvMap, api_timeout: Option<u64>) {
+    if api_timeout.is_some() && uses_old_wait_timeout(&args.subcommand) {
+        println!(
+            "{} --timeout now sets the timeout of each API request, use --wait-timeout to set how long to wait. Pass both to set the two",
+            "✗".red()
+        );
+        std::process::exit(1);
+    }
+
+    if let Some(context) = &context {
+        if is_local_only(&args.subcommand) {
+            println!(
+                "{} This command only works on the instance in the current directory, not on context {}. Use --context {}",
+                "✗".red(),
+                context.name,
+                DEFAULT_CONTEXT
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let client = create_client(context.as_ref(), &env_map, api_timeout);
 
     match args.subcommand {
+        AppSubcommand::Install(args) => {
+            install::run(args, &client, &env_map);
+        }
         AppSubcommand::Start(args) => {
-            let spin = spinner::new(&format!("Starting app {}...", args.id));
-            let url = format!("{}/{}/{}", base_url, args.id, "start");
-            let api_response = api_request(url, Method::POST, "{}");
-            let error_message = format!("Failed to start app {}. See logs/error.log for more details.", args.id);
-            handle_api_response(spin, api_response, "App started successfully!", &error_message);
+            run_selected_action(&client, &AppLifecycleRequest::Start, &args.selector, &args.wait);
         }
         AppSubcommand::Stop(args) => {
-            let spin = spinner::new(&format!("Stopping app {}...", args.id));
-            let url = format!("{}/{}/{}", base_url, args.id, "stop");
-            let api_response = api_request(url, Method::POST, "{}");
-            let error_message = format!("Failed to stop app {}. See logs/error.log for more details.", args.id);
-            handle_api_response(spin, api_response, "App stopped successfully!", &error_message);
+            run_selected_action(&client, &AppLifecycleRequest::Stop, &args.selector, &args.wait);
         }
         AppSubcommand::Uninstall(args) => {
-            let spin = spinner::new(&format!("Uninstalling app {}...", args.id));
-            let url = format!("{}/{}/{}", base_url, args.id, "uninstall");
-            let api_response = api_request(url, Method::DELETE, "{\"removeBackups\": false}");
-            let error_message = format!("Failed to uninstall app {}. See logs/error.log for more details.", args.id);
-            handle_api_response(spin, api_response, "App uninstalled successfully!", &error_message);
+            let body = UninstallAppBody {
+                remove_backups: args.remove_backups,
+            };
+            run_single_action(&client, &AppLifecycleRequest::Uninstall(body), &args.id, &args.wait);
         }
         AppSubcommand::Reset(args) => {
-            let spin = spinner::new(&format!("Resetting app {}...", args.id));
-            let url = format!("{}/{}/{}", base_url, args.id, "reset");
-            let api_response = api_request(url, Method::POST, "{}");
-            let error_message = format!("Failed to reset app {}. See logs/error.log for more details.", args.id);
-            handle_api_response(spin, api_response, "App reset successfully!", &error_message);
+            run_selected_action(&client, &AppLifecycleRequest::Reset, &args.selector, &args.wait);
         }
         AppSubcommand::Update(args) => {
-            let spin = spinner::new(&format!("Updating app {}...", args.id));
-            let url = format!("{}/{}/{}", base_url, args.id, "update");
-            let api_response = api_request(url, Method::PATCH, "{\"performBackup\": true}");
-            let error_message = format!("Failed to update app {}. See logs/error.log for more details.", args.id);
-            handle_api_response(spin, api_response, "App updated successfully!", &error_message);
+            update::run(args, &client, &env_map);
+        }
+        AppSubcommand::StartAll(args) => {
+            start_all(args, &client);
+        }
+        AppSubcommand::List(args) => {
+            list::run(args, &client, &env_map);
+        }
+        AppSubcommand::Info(args) => {
+            info::run(args, &env_map);
+        }
+        AppSubcommand::Config(args) => {
+            config::run(args, &client);
         }
-        AppSubcommand::StartAll(_) => {
-            panic!("Not implemented yet");
+        AppSubcommand::Logs(args) => {
+            logs::run(args);
+        }
+        AppSubcommand::Exec(args) => {
+            exec::exec(args);
+        }
+        AppSubcommand::Shell(args) => {
+            exec::shell(args);
+        }
+        AppSubcommand::Backup(args) => {
+            backup::backup(args, &client);
+        }
+        AppSubcommand::Backups(args) => {
+            backup::list(args);
+        }
+        AppSubcommand::Restore(args) => {
+            backup::restore(args, &client);
         }
     }
 }
+
+fn selection_pattern(selector: &AppSelector) -> Result<Option<Regex>, String> {
+    match (&selector.glob, &selector.regex) {
+        (Some(glob), _) => glob_to_regex(glob).map(Some).map_err(|e| format!("Invalid glob pattern: {}", e)),
+        (_, Some(regex)) => Regex::new(regex).map(Some).map_err(|e| format!("Invalid regular expression: {}", e)),
+        _ => Ok(None),
+    }
+}
+
+/// The installed apps matching `pattern`, asked to the dashboard for a remote instance and read from disk otherwise
+fn installed_app_ids(client: &RuntipiClient, pattern: Option<&Regex>) -> Result<Vec<String>, String> {
+    if client.is_remote() {
+        let response = client.installed_apps().map_err(|e| format!("Failed to list installed apps: {}", e))?;
+
+        return Ok(response
+            .installed
+            .into_iter()
+            .map(|app| app.app.id)
+            .filter(|id| {
+                let name = id.split(':').next().unwrap_or(id);
+                pattern.is_none_or(|pattern| pattern.is_match(id) || pattern.is_match(name))
+            })
+            .collect());
+    }
+
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+    let apps = get_installed_apps(&root_folder).map_err(|e| format!("Failed to list installed apps: {}", e))?;
+
+    Ok(select_app_ids(&apps, pattern))
+}
+
+fn resolve_selection(client: &RuntipiClient, selector: &AppSelector) -> Result<Vec<String>, String> {
+    if !selector.is_selection() {
+        return Ok(selector.ids.clone());
+    }
+
+    let pattern = selection_pattern(selector)?;
+
+    installed_app_ids(client, pattern.as_ref())
+}
+
+fn run_selected_action(client: &RuntipiClient, request: &AppLifecycleRequest, selector: &AppSelector, wait: &WaitArgs) {
+    let ids = match resolve_selection(client, selector) {
+        Ok(ids) => ids,
+        Err(err) => {
+            println!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if !selector.is_bulk() {
+        run_single_action(client, request, &ids[0], wait);
+        return;
+    }
+
+    if ids.is_empty() {
+        println!("No installed apps match the selection");
+        return;
+    }
+
+    run_batch_action(client, request, &ids, selector.parallel, wait);
+}
+
+/// Run the action on every app without stopping at the first failure, then print a summary
+fn run_batch_action(client: &RuntipiClient, request: &AppLifecycleRequest, ids: &[String], parallel: usize, wait: &WaitArgs) {
+    let action = LifecycleAction::of(request);
+    let batch_action = BatchAction {
+        progress: action.progress,
+        done: action.done,
+    };
+
+    let results = run_batch(ids, parallel, &batch_action, |id| run_lifecycle_action(client, request, id, wait));
+
+    print_summary(&results, &batch_action);
+
+    if has_failures(&results) {
+        std::process::exit(1);
+    }
+}
+
+fn start_all(args: StartAll, client: &RuntipiClient) {
+    let ids = match installed_app_ids(client, None) {
+        Ok(ids) => ids,
+        Err(err) => {
+            println!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if ids.is_empty() {
+        println!("No installed apps found");
+        return;
+    }
+
+    run_batch_action(client, &AppLifecycleRequest::Start, &ids, args.parallel.get(), &args.wait);
+}
@@ -0,0 +1,151 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::args::{BackupApp, ListBackups, RestoreApp};
+use crate::components::spinner;
+use crate::utils::api::{AppLifecycleRequest, RuntipiClient};
+use crate::utils::apps::{find_installed_app, InstalledApp};
+use crate::utils::backups::{list_archives, prune_archives, BackupArchive};
+use crate::utils::schemas::RestoreAppBody;
+
+use super::run_single_action;
+use super::wait::ErrorLogCursor;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn find_app_or_exit(id: &str) -> (PathBuf, InstalledApp) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    match find_installed_app(&root_folder, id) {
+        Ok(app) => (root_folder, app),
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Wait for a new archive to show up and for its size to stop changing
+fn wait_for_new_archive(backups_path: &Path, existing: &[String], timeout: Duration) -> Option<BackupArchive> {
+    let started_at = Instant::now();
+    let mut last_size = None;
+
+    while started_at.elapsed() < timeout {
+        thread::sleep(POLL_INTERVAL);
+
+        let new_archive = list_archives(backups_path).into_iter().find(|archive| !existing.contains(&archive.name));
+
+        if let Some(archive) = new_archive {
+            if last_size == Some(archive.size) {
+                return Some(archive);
+            }
+            last_size = Some(archive.size);
+        }
+    }
+
+    None
+}
+
+pub fn backup(args: BackupApp, client: &RuntipiClient) {
+    let (root_folder, app) = find_app_or_exit(&args.id);
+    let backups_path = app.backups_path(&root_folder);
+    let existing = list_archives(&backups_path)
+        .into_iter()
+        .map(|archive| archive.name)
+        .collect::<Vec<String>>();
+
+    let spin = spinner::new(&format!("Creating a backup of app {}...", app.id));
+    let error_log = ErrorLogCursor::new();
+
+    if let Err(err) = client.app_lifecycle(&app.id, &AppLifecycleRequest::Backup) {
+        spin.fail(&format!("Failed to backup app {}", app.id));
+        spin.finish();
+        println!("Error: {}", err);
+        std::process::exit(1);
+    }
+
+    match wait_for_new_archive(&backups_path, &existing, args.timeout()) {
+        Some(archive) => spin.succeed(&format!("Backup {} created ({})", archive.name, format_size(archive.size))),
+        None => {
+            spin.fail(&format!("Failed to backup app {}", app.id));
+            spin.finish();
+            match error_log.new_errors(&app.id).last() {
+                Some(error) => println!("Error: {}", error),
+                None => println!("Error: No new archive in {} after {}s", backups_path.display(), args.timeout().as_secs()),
+            }
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(keep) = args.keep {
+        spin.set_message(&format!("Keeping the {} most recent backups...", keep));
+        let removed = prune_archives(&backups_path, keep);
+
+        if !removed.is_empty() {
+            spin.succeed(&format!("Removed {} old backup(s): {}", removed.len(), removed.join(", ")));
+        }
+    }
+
+    spin.finish();
+}
+
+pub fn list(args: ListBackups) {
+    let (root_folder, app) = find_app_or_exit(&args.id);
+    let archives = list_archives(&app.backups_path(&root_folder));
+
+    if archives.is_empty() {
+        println!("No backups found for app {}", app.id);
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["Archive", "Size", "Date"]);
+
+    for archive in archives {
+        let date: DateTime<Local> = archive.modified.into();
+        table.add_row(row![archive.name, format_size(archive.size), date.format("%Y-%m-%d %H:%M:%S")]);
+    }
+
+    table.printstd();
+}
+
+pub fn restore(args: RestoreApp, client: &RuntipiClient) {
+    let (root_folder, app) = find_app_or_exit(&args.id);
+
+    // Accept both a bare archive name and a path to an archive in the backups folder
+    let archive = Path::new(&args.archive).file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    if !app.backups_path(&root_folder).join(&archive).exists() {
+        println!(
+            "{} Backup {} not found. Run `app backups {}` to see the available archives",
+            "✗".red(),
+            archive,
+            app.id
+        );
+        std::process::exit(1);
+    }
+
+    let request = AppLifecycleRequest::Restore(RestoreAppBody { filename: archive });
+    run_single_action(client, &request, &app.id, &args.wait);
+}
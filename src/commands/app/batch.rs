@@ -0,0 +1,90 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use colored::Colorize;
+use indicatif::MultiProgress;
+use prettytable::{format, row, Table};
+
+use crate::components::spinner;
+
+pub struct BatchAction<'a> {
+    /// Shown while the operation runs, eg: "Starting"
+    pub progress: &'a str,
+    /// Shown once the operation succeeded, eg: "started"
+    pub done: &'a str,
+}
+
+pub struct BatchResult {
+    pub id: String,
+    pub outcome: Result<(), String>,
+}
+
+/**
+* Run `operation` for every id, in order, with at most `parallel` operations in flight.
+* A failing app does not stop the batch, every outcome is returned in the order of `ids`.
+*/
+pub fn run_batch<F>(ids: &[String], parallel: usize, action: &BatchAction, operation: F) -> Vec<BatchResult>
+where
+    F: Fn(&str) -> Result<(), String> + Sync,
+{
+    let multi = MultiProgress::new();
+    let next_index = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<Option<Result<(), String>>>> = Mutex::new(vec![None; ids.len()]);
+    let workers = parallel.clamp(1, ids.len().max(1));
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                let Some(id) = ids.get(index) else {
+                    break;
+                };
+
+                let spin = spinner::new_in(&multi, &format!("{} app {}...", action.progress, id));
+                let outcome = operation(id);
+
+                match &outcome {
+                    Ok(_) => spin.succeed(&format!("App {} {}", id, action.done)),
+                    Err(err) => spin.fail(&format!("App {} failed: {}", id, err)),
+                }
+                spin.finish();
+
+                outcomes.lock().unwrap()[index] = Some(outcome);
+            });
+        }
+    });
+
+    let outcomes = outcomes.into_inner().unwrap();
+
+    ids.iter()
+        .zip(outcomes)
+        .map(|(id, outcome)| BatchResult {
+            id: id.clone(),
+            outcome: outcome.unwrap_or(Err("Not run".to_string())),
+        })
+        .collect()
+}
+
+pub fn print_summary(results: &[BatchResult], action: &BatchAction) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["App", "Result", "Details"]);
+
+    for result in results {
+        match &result.outcome {
+            Ok(_) => table.add_row(row![result.id, action.done.green(), ""]),
+            Err(err) => table.add_row(row![result.id, "failed".red(), err]),
+        };
+    }
+
+    let failed = results.iter().filter(|result| result.outcome.is_err()).count();
+
+    println!();
+    table.printstd();
+    println!("{} succeeded, {} failed", results.len() - failed, failed);
+}
+
+pub fn has_failures(results: &[BatchResult]) -> bool {
+    results.iter().any(|result| result.outcome.is_err())
+}
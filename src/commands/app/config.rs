@@ -0,0 +1,203 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use colored::Colorize;
+
+use crate::args::{AppConfig, AppConfigCommand, AppConfigFile};
+use crate::components::diff::print_diff;
+use crate::components::spinner;
+use crate::utils::api::{AppLifecycleRequest, RuntipiClient};
+use crate::utils::apps::{find_installed_app, InstalledApp};
+use crate::utils::env::{env_string_to_map, set_env_value, unset_env_value, validate_env_string};
+use crate::utils::form::parse_key_value;
+
+use super::handle_api_response;
+
+impl AppConfigFile {
+    fn file_name(&self) -> &'static str {
+        match self {
+            AppConfigFile::Compose => "docker-compose.yml",
+            AppConfigFile::Env => "app.env",
+        }
+    }
+
+    fn validate(&self, content: &str) -> Result<(), Vec<String>> {
+        match self {
+            AppConfigFile::Compose => validate_compose_override(content),
+            AppConfigFile::Env => validate_env_string(content),
+        }
+    }
+}
+
+fn validate_compose_override(content: &str) -> Result<(), Vec<String>> {
+    if content.trim().is_empty() {
+        return Ok(());
+    }
+
+    match serde_yaml::from_str::<serde_yaml::Value>(content) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => match mapping.get("services") {
+            None | Some(serde_yaml::Value::Mapping(_)) => Ok(()),
+            Some(_) => Err(vec!["'services' must be a mapping of service names".to_string()]),
+        },
+        Ok(_) => Err(vec!["The file must be a YAML mapping".to_string()]),
+        Err(e) => Err(vec![e.to_string()]),
+    }
+}
+
+/// Validate, show the diff and write the new content. Returns false when nothing was written
+fn save(user_config_path: &Path, file: AppConfigFile, old: &str, new: &str) -> bool {
+    if let Err(errors) = file.validate(new) {
+        println!("{} Invalid {}, nothing was saved:", "✗".red(), file.file_name());
+        for error in errors {
+            println!("  - {}", error);
+        }
+        std::process::exit(1);
+    }
+
+    if !print_diff(old, new) {
+        println!("No changes to {}", file.file_name());
+        return false;
+    }
+
+    let path = user_config_path.join(file.file_name());
+
+    let written = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&path, new));
+
+    match written {
+        Ok(_) => {
+            println!("{} Saved {}", "✓".green(), path.display());
+            true
+        }
+        Err(e) => {
+            println!("{} Unable to write {}: {}", "✗".red(), path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn edit(path: &Path, file: AppConfigFile, current: &str) -> String {
+    let editor = env::var("VISUAL").or(env::var("EDITOR")).unwrap_or("vi".to_string());
+
+    let temp_file = tempfile::Builder::new()
+        .prefix("runtipi-")
+        .suffix(&format!("-{}", file.file_name()))
+        .tempfile()
+        .and_then(|temp_file| fs::write(temp_file.path(), current).map(|_| temp_file));
+
+    let temp_file = match temp_file {
+        Ok(temp_file) => temp_file,
+        Err(e) => {
+            println!("{} Unable to create a temporary file: {}", "✗".red(), e);
+            std::process::exit(1);
+        }
+    };
+
+    // The editor may contain arguments, eg: "code --wait"
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(temp_file.path())
+        .status();
+
+    match status {
+        Ok(status) if status.success() => fs::read_to_string(temp_file.path()).unwrap_or_default(),
+        _ => {
+            println!(
+                "{} Editor '{}' exited with an error, {} was not modified",
+                "✗".red(),
+                editor,
+                path.display()
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn restart(app: &InstalledApp, client: &RuntipiClient) {
+    let spin = spinner::new(&format!("Restarting app {}...", app.id));
+    let api_response = client.app_lifecycle(&app.id, &AppLifecycleRequest::Start);
+    let error_message = format!("Failed to restart app {}. See logs/error.log for more details.", app.id);
+    handle_api_response(spin, api_response, "App restarted with the new configuration!", &error_message);
+}
+
+pub fn run(args: AppConfig, client: &RuntipiClient) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    let app = match find_installed_app(&root_folder, &args.id) {
+        Ok(app) => app,
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    };
+
+    let user_config_path = app.user_config_path(&root_folder);
+    let read = |file: AppConfigFile| fs::read_to_string(user_config_path.join(file.file_name())).unwrap_or_default();
+
+    let (changed, restart_app) = match args.command {
+        AppConfigCommand::Show => {
+            for file in [AppConfigFile::Compose, AppConfigFile::Env] {
+                let path = user_config_path.join(file.file_name());
+                println!("--- {} ---", path.display().to_string().blue());
+
+                match fs::read_to_string(&path) {
+                    Ok(content) => println!("{}", content.trim_end()),
+                    Err(_) => println!("{}", "Not set".bright_white()),
+                }
+                println!();
+            }
+            (false, false)
+        }
+        AppConfigCommand::Get { key } => match env_string_to_map(&read(AppConfigFile::Env)).get(&key) {
+            Some(value) => {
+                println!("{}", value);
+                (false, false)
+            }
+            None => {
+                println!("{} {} is not set in the app.env override of {}", "✗".red(), key, app.id);
+                std::process::exit(1);
+            }
+        },
+        AppConfigCommand::Set { values, restart } => {
+            let old = read(AppConfigFile::Env);
+            let mut new = old.clone();
+
+            for raw in values {
+                match parse_key_value(&raw) {
+                    Ok((key, value)) => new = set_env_value(&new, &key, &value),
+                    Err(err) => {
+                        println!("{} {}", "✗".red(), err);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            (save(&user_config_path, AppConfigFile::Env, &old, &new), restart)
+        }
+        AppConfigCommand::Unset { keys, restart } => {
+            let old = read(AppConfigFile::Env);
+            let new = keys.iter().fold(old.clone(), |content, key| unset_env_value(&content, key));
+
+            (save(&user_config_path, AppConfigFile::Env, &old, &new), restart)
+        }
+        AppConfigCommand::Edit { file, restart } => {
+            let old = read(file);
+            let new = edit(&user_config_path.join(file.file_name()), file, &old);
+
+            (save(&user_config_path, file, &old, &new), restart)
+        }
+    };
+
+    if changed && restart_app {
+        restart(&app, client);
+    } else if changed {
+        println!("Restart the app to apply the changes, eg: runtipi-cli app start {}", app.id);
+    }
+}
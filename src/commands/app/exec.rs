@@ -0,0 +1,86 @@
+use std::env;
+use std::io::IsTerminal;
+use std::process::Command;
+
+use colored::Colorize;
+
+use crate::args::{AppExec, AppShell};
+use crate::utils::apps::find_installed_app;
+use crate::utils::docker::{get_compose_containers, ContainerInfo};
+
+/// Pick the container to attach to: the requested service, the service named like the app or the only running one
+fn resolve_container(id: &str, service: Option<&str>) -> Result<ContainerInfo, String> {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+    let app = find_installed_app(&root_folder, id).map_err(|e| e.to_string())?;
+
+    let running = get_compose_containers(Some(&app.compose_project()))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|container| container.is_running())
+        .collect::<Vec<ContainerInfo>>();
+
+    if running.is_empty() {
+        return Err(format!("App {} has no running container. Is it started?", app.id));
+    }
+
+    let services = running
+        .iter()
+        .map(|container| container.service.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    let container = match service {
+        Some(service) => running.iter().find(|container| container.service == service),
+        None if running.len() == 1 => running.first(),
+        None => running.iter().find(|container| container.service == app.name),
+    };
+
+    match (container, service) {
+        (Some(container), _) => Ok(container.clone()),
+        (None, Some(service)) => Err(format!("Service {} is not running, running services: {}", service, services)),
+        (None, None) => Err(format!("App {} has several services, pick one with --service: {}", app.id, services)),
+    }
+}
+
+fn docker_exec(container: &ContainerInfo, command: &[String]) {
+    let mut docker = Command::new("docker");
+    docker.arg("exec").arg("--interactive");
+
+    // Only ask for a TTY when we have one, so piping into the command keeps working
+    if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        docker.arg("--tty");
+    }
+
+    match docker.arg(&container.name).args(command).status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => {
+            println!("{} Failed to run docker exec: {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn resolve_container_or_exit(id: &str, service: Option<&str>) -> ContainerInfo {
+    match resolve_container(id, service) {
+        Ok(container) => container,
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn exec(args: AppExec) {
+    let container = resolve_container_or_exit(&args.id, args.service.as_deref());
+    docker_exec(&container, &args.command);
+}
+
+pub fn shell(args: AppShell) {
+    let container = resolve_container_or_exit(&args.id, args.service.as_deref());
+    let command = ["sh", "-c", "if command -v bash > /dev/null; then exec bash; else exec sh; fi"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>();
+
+    docker_exec(&container, &command);
+}
@@ -0,0 +1,181 @@
+use std::env;
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+use serde::Serialize;
+
+use crate::args::AppInfo;
+use crate::utils::apps::find_installed_app;
+use crate::utils::backups::list_archives;
+use crate::utils::docker::get_compose_containers;
+use crate::utils::env::{env_string_to_map, EnvMap};
+
+#[derive(Serialize, Debug)]
+struct ContainerDetails {
+    name: String,
+    service: String,
+    status: String,
+    ports: String,
+}
+
+#[derive(Serialize, Debug)]
+struct UserConfigDetails {
+    path: String,
+    docker_compose: bool,
+    app_env: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct AppDetails {
+    id: String,
+    name: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    source: Option<String>,
+    categories: Vec<String>,
+    installed_version: Option<String>,
+    latest_version: Option<String>,
+    update_available: bool,
+    containers: Vec<ContainerDetails>,
+    port: Option<String>,
+    exposed: bool,
+    domain: Option<String>,
+    data_path: String,
+    backups: usize,
+    user_config: UserConfigDetails,
+}
+
+fn collect_info(id: &str, env_map: &EnvMap) -> Result<AppDetails, String> {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+    let app = find_installed_app(&root_folder, id).map_err(|e| e.to_string())?;
+
+    let config = app.config();
+    let repo_config = app.repo_config(&root_folder, env_map);
+    let metadata = repo_config.as_ref().or(config.as_ref());
+
+    let data_path = app.data_path(env_map);
+    let app_env = env_string_to_map(&std::fs::read_to_string(data_path.join("app.env")).unwrap_or_default());
+
+    let containers = get_compose_containers(Some(&app.compose_project()))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|container| ContainerDetails {
+            name: container.name,
+            service: container.service,
+            status: container.status,
+            ports: container.ports,
+        })
+        .collect();
+
+    let user_config_path = app.user_config_path(&root_folder);
+
+    Ok(AppDetails {
+        id: app.id.clone(),
+        name: metadata.map(|config| config.name.clone()),
+        description: metadata.map(|config| config.short_desc.clone()),
+        author: metadata.and_then(|config| config.author.clone()),
+        source: metadata.and_then(|config| config.source.clone()),
+        categories: metadata.map(|config| config.categories.clone()).unwrap_or_default(),
+        installed_version: config.as_ref().map(|config| config.version.clone()),
+        latest_version: repo_config.as_ref().map(|config| config.version.clone()),
+        update_available: app.available_update(&root_folder, env_map).is_some(),
+        containers,
+        port: app_env
+            .get("APP_PORT")
+            .cloned()
+            .or(config.as_ref().and_then(|config| config.port).map(|port| port.to_string())),
+        exposed: app_env.get("APP_EXPOSED").is_some_and(|exposed| exposed == "true"),
+        domain: app_env.get("APP_DOMAIN").filter(|domain| !domain.is_empty()).cloned(),
+        data_path: data_path.display().to_string(),
+        backups: list_archives(&app.backups_path(&root_folder)).len(),
+        user_config: UserConfigDetails {
+            docker_compose: user_config_path.join("docker-compose.yml").exists(),
+            app_env: user_config_path.join("app.env").exists(),
+            path: user_config_path.display().to_string(),
+        },
+    })
+}
+
+fn yes_no(value: bool) -> colored::ColoredString {
+    if value {
+        "Yes".yellow()
+    } else {
+        "No".bright_white()
+    }
+}
+
+fn print_table(info: AppDetails) {
+    let not_set = || "-".to_string();
+
+    println!("--- {} ---", "App".blue());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(row!["ID", info.id]);
+    table.add_row(row!["Name", info.name.unwrap_or_else(not_set)]);
+    table.add_row(row!["Description", info.description.unwrap_or_else(not_set)]);
+    table.add_row(row!["Author", info.author.unwrap_or_else(not_set)]);
+    table.add_row(row!["Source", info.source.unwrap_or_else(not_set)]);
+    table.add_row(row!["Categories", info.categories.join(", ")]);
+    table.add_row(row!["Installed version", info.installed_version.unwrap_or_else(not_set)]);
+    let latest_version = info.latest_version.unwrap_or_else(not_set);
+    table.add_row(row![
+        "Latest version",
+        if info.update_available {
+            latest_version.green()
+        } else {
+            latest_version.normal()
+        }
+    ]);
+    table.printstd();
+
+    println!("\n--- {} ---", "Network".blue());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(row!["Port", info.port.unwrap_or_else(not_set)]);
+    table.add_row(row!["Exposed", yes_no(info.exposed)]);
+    table.add_row(row!["Domain", info.domain.unwrap_or_else(not_set)]);
+    table.printstd();
+
+    println!("\n--- {} ---", "Containers".blue());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["Name", "Service", "Status", "Ports"]);
+    for container in &info.containers {
+        let status = if container.status.starts_with("Up") && !container.status.contains("unhealthy") {
+            container.status.green()
+        } else {
+            container.status.red()
+        };
+        table.add_row(row![container.name, container.service, status, container.ports]);
+    }
+    if info.containers.is_empty() {
+        table.add_row(row!["No containers found"]);
+    }
+    table.printstd();
+
+    println!("\n--- {} ---", "Storage".blue());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(row!["Data path", info.data_path]);
+    table.add_row(row!["Backups", info.backups]);
+    table.add_row(row!["User config", info.user_config.path]);
+    table.add_row(row!["Custom docker-compose.yml", yes_no(info.user_config.docker_compose)]);
+    table.add_row(row!["Custom app.env", yes_no(info.user_config.app_env)]);
+    table.printstd();
+}
+
+pub fn run(args: AppInfo, env_map: &EnvMap) {
+    let info = match collect_info(&args.id, env_map) {
+        Ok(info) => info,
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&info).unwrap_or_default());
+    } else {
+        print_table(info);
+    }
+}
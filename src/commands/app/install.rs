@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use colored::Colorize;
+use serde_json::{Map, Value};
+
+use crate::args::InstallApp;
+use crate::utils::api::{AppLifecycleRequest, RuntipiClient};
+use crate::utils::apps::find_repo_app_config;
+use crate::utils::env::EnvMap;
+use crate::utils::form::{parse_key_value, validate_form_values};
+
+use super::run_single_action;
+
+fn collect_values(args: &InstallApp) -> Result<BTreeMap<String, Value>, String> {
+    let mut values = BTreeMap::new();
+
+    if let Some(values_file) = &args.values {
+        let content = std::fs::read_to_string(values_file).map_err(|e| format!("Unable to read {}: {}", values_file.display(), e))?;
+
+        match serde_json::from_str::<Value>(&content) {
+            Ok(Value::Object(map)) => values.extend(map),
+            Ok(_) => return Err(format!("{} must contain a JSON object", values_file.display())),
+            Err(e) => return Err(format!("{} is not valid JSON: {}", values_file.display(), e)),
+        }
+    }
+
+    // Values passed with --set take precedence over the values file
+    for raw in &args.set {
+        let (key, value) = parse_key_value(raw)?;
+        values.insert(key, Value::String(value));
+    }
+
+    Ok(values)
+}
+
+pub fn run(args: InstallApp, client: &RuntipiClient, env_map: &EnvMap) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    let (app_name, store) = match args.id.split_once(':') {
+        Some((name, store)) => (name, Some(store)),
+        None => (args.id.as_str(), env_map.get("APPS_REPO_ID").map(|s| s.as_str())),
+    };
+
+    let values = match collect_values(&args) {
+        Ok(values) => values,
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    };
+
+    // The repositories on this machine may differ from the ones of a remote instance, its dashboard validates the values
+    if client.is_remote() {
+        let form = values.into_iter().collect::<Map<String, Value>>();
+        run_single_action(client, &AppLifecycleRequest::Install(form), &args.id, &args.wait);
+        return;
+    }
+
+    let config = match find_repo_app_config(&root_folder, app_name, store) {
+        Some(config) => config,
+        None => {
+            println!("{} App {} not found in the app store repositories under repos/", "✗".red(), args.id);
+            std::process::exit(1);
+        }
+    };
+
+    let form = match validate_form_values(&config.form_fields, &values) {
+        Ok(form) => form,
+        Err(errors) => {
+            println!("{} Invalid values for app {}:", "✗".red(), args.id);
+            for error in errors {
+                println!("  - {}", error);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    run_single_action(client, &AppLifecycleRequest::Install(form), &args.id, &args.wait);
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::args::ListApps;
+use crate::utils::api::RuntipiClient;
+use crate::utils::apps::get_installed_apps;
+use crate::utils::docker::get_compose_containers;
+use crate::utils::env::{env_string_to_map, EnvMap};
+use crate::utils::schemas::InstalledAppsResponse;
+
+struct AppRow {
+    id: String,
+    version: String,
+    status: String,
+    url: Option<String>,
+    update_available: bool,
+}
+
+/// `host` is the machine running the apps, the `INTERNAL_IP` of the local instance or the API host of a remote one
+fn app_url(host: &str, exposed: bool, domain: Option<&str>, port: Option<i64>) -> Option<String> {
+    match (exposed, domain, port) {
+        (true, Some(domain), _) if !domain.is_empty() => Some(format!("https://{}", domain)),
+        (_, _, Some(port)) => Some(format!("http://{}:{}", host, port)),
+        _ => None,
+    }
+}
+
+fn local_host(env_map: &EnvMap) -> String {
+    env_map.get("INTERNAL_IP").cloned().unwrap_or("localhost".to_string())
+}
+
+fn rows_from_api(client: &RuntipiClient, env_map: &EnvMap) -> Result<Vec<AppRow>, String> {
+    let parsed: InstalledAppsResponse = client.installed_apps().map_err(|e| e.to_string())?;
+    let host = client.remote_host().unwrap_or_else(|| local_host(env_map));
+
+    let rows = parsed
+        .installed
+        .into_iter()
+        .map(|installed| {
+            let latest_version = installed.metadata.and_then(|metadata| metadata.latest_version).unwrap_or(0);
+            let info_port = installed.info.as_ref().and_then(|info| info.port);
+
+            AppRow {
+                url: app_url(
+                    &host,
+                    installed.app.exposed,
+                    installed.app.domain.as_deref(),
+                    installed.app.port.or(info_port),
+                ),
+                update_available: latest_version > installed.app.version,
+                version: installed.info.map(|info| info.version).unwrap_or_default(),
+                status: installed.app.status,
+                id: installed.app.id,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+fn rows_from_disk(root_folder: &Path, env_map: &EnvMap) -> Result<Vec<AppRow>, String> {
+    let apps = get_installed_apps(root_folder).map_err(|e| e.to_string())?;
+    let host = local_host(env_map);
+
+    // Group the container states by compose project so we only call docker once
+    let mut project_states: HashMap<String, Vec<bool>> = HashMap::new();
+    for container in get_compose_containers(None).unwrap_or_default() {
+        project_states.entry(container.project.clone()).or_default().push(container.is_running());
+    }
+
+    let rows = apps
+        .iter()
+        .map(|app| {
+            let config = app.config();
+            let app_env_file = std::fs::read_to_string(app.data_path(env_map).join("app.env")).unwrap_or_default();
+            let app_env = env_string_to_map(&app_env_file);
+
+            let status = match project_states.get(&app.compose_project()) {
+                Some(states) if states.iter().any(|running| *running) => "running",
+                Some(_) => "stopped",
+                None => "missing",
+            };
+
+            let port = app_env
+                .get("APP_PORT")
+                .and_then(|port| port.parse::<i64>().ok())
+                .or(config.as_ref().and_then(|config| config.port));
+
+            AppRow {
+                id: app.id.clone(),
+                version: config.as_ref().map(|config| config.version.clone()).unwrap_or_default(),
+                status: status.to_string(),
+                url: app_url(
+                    &host,
+                    app_env.get("APP_EXPOSED").is_some_and(|exposed| exposed == "true"),
+                    app_env.get("APP_DOMAIN").map(|s| s.as_str()),
+                    port,
+                ),
+                update_available: app.available_update(root_folder, env_map).is_some(),
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+pub fn run(args: ListApps, client: &RuntipiClient, env_map: &EnvMap) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    let rows = match rows_from_api(client, env_map) {
+        Ok(rows) => rows,
+        Err(api_err) if client.is_remote() => {
+            println!("Failed to list installed apps: {}", api_err);
+            std::process::exit(1);
+        }
+        Err(api_err) => {
+            println!(
+                "{} Unable to reach the dashboard API ({}), reading installed apps from disk",
+                "⚠".yellow(),
+                api_err
+            );
+
+            match rows_from_disk(&root_folder, env_map) {
+                Ok(rows) => rows,
+                Err(err) => {
+                    println!("Failed to list installed apps: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    };
+
+    let rows = rows
+        .into_iter()
+        .filter(|row| !args.running || row.status == "running")
+        .filter(|row| !args.stopped || row.status != "running")
+        .filter(|row| !args.updates_available || row.update_available)
+        .collect::<Vec<AppRow>>();
+
+    if rows.is_empty() {
+        println!("No apps found");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["ID", "Version", "Status", "URL"]);
+
+    for row in rows {
+        let version = if row.update_available {
+            format!("{} {}", row.version, "(update available)".yellow())
+        } else {
+            row.version
+        };
+        let status = match row.status.as_str() {
+            "running" => row.status.green(),
+            "stopped" | "missing" => row.status.red(),
+            _ => row.status.yellow(),
+        };
+
+        table.add_row(row![row.id, version, status, row.url.unwrap_or("-".to_string())]);
+    }
+
+    table.printstd();
+}
@@ -0,0 +1,58 @@
+use std::env;
+use std::process::Command;
+
+use colored::Colorize;
+
+use crate::args::AppLogs;
+use crate::utils::apps::find_installed_app;
+use crate::utils::docker::get_compose_containers;
+
+pub fn run(args: AppLogs) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    let app = match find_installed_app(&root_folder, &args.id) {
+        Ok(app) => app,
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    };
+
+    let project = app.compose_project();
+
+    match get_compose_containers(Some(&project)) {
+        Ok(containers) if containers.is_empty() => {
+            println!("{} No containers found for app {}. Is it started?", "✗".red(), app.id);
+            std::process::exit(1);
+        }
+        Ok(_) => {}
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    }
+
+    // docker compose takes care of streaming and of coloring the service prefixes
+    let mut command = Command::new("docker");
+    command.arg("compose").arg("--project-name").arg(&project).arg("logs");
+
+    if args.follow {
+        command.arg("--follow");
+    }
+    if let Some(tail) = args.tail {
+        command.arg("--tail").arg(tail.to_string());
+    }
+    if let Some(since) = &args.since {
+        command.arg("--since").arg(since);
+    }
+    command.args(&args.service);
+
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => {
+            println!("{} Failed to run docker compose: {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    }
+}
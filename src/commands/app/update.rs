@@ -0,0 +1,118 @@
+use std::env;
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+use regex::Regex;
+
+use crate::args::UpdateApp;
+use crate::components::prompt::confirm;
+use crate::utils::api::{AppLifecycleRequest, RuntipiClient};
+use crate::utils::apps::{get_installed_apps, select_app_ids, AppUpdate};
+use crate::utils::env::EnvMap;
+use crate::utils::schemas::UpdateAppBody;
+
+use super::{installed_app_ids, run_batch_action, run_selected_action, selection_pattern};
+
+/// The selected apps with a newer version, compared by the dashboard for a remote instance and with repos/ otherwise
+fn available_updates(client: &RuntipiClient, pattern: Option<&Regex>, env_map: &EnvMap) -> Result<Vec<(String, AppUpdate)>, String> {
+    if client.is_remote() {
+        let selected_ids = installed_app_ids(client, pattern)?;
+        let response = client.installed_apps().map_err(|e| format!("Failed to list installed apps: {}", e))?;
+
+        return Ok(response
+            .installed
+            .into_iter()
+            .filter(|installed| selected_ids.contains(&installed.app.id))
+            .filter_map(|installed| {
+                let metadata = installed.metadata?;
+                let latest_tipi_version = metadata.latest_version.filter(|latest| *latest > installed.app.version)?;
+
+                let update = AppUpdate {
+                    current_version: installed.info.map(|info| info.version).unwrap_or_default(),
+                    current_tipi_version: installed.app.version,
+                    latest_version: metadata.latest_docker_version.unwrap_or_default(),
+                    latest_tipi_version,
+                };
+                Some((installed.app.id, update))
+            })
+            .collect());
+    }
+
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+    let apps = get_installed_apps(&root_folder).map_err(|e| format!("Failed to list installed apps: {}", e))?;
+    let selected_ids = select_app_ids(&apps, pattern);
+
+    let mut updates = vec![];
+    let mut skipped = vec![];
+
+    for app in apps.iter().filter(|app| selected_ids.contains(&app.id)) {
+        match app.config() {
+            Some(current) => updates.extend(app.update_from(current, &root_folder, env_map).map(|update| (app.id.clone(), update))),
+            None => skipped.push(app.id.clone()),
+        }
+    }
+
+    for id in skipped {
+        println!("{} Skipping {}, its config.json can't be read", "⚠".yellow(), id);
+    }
+
+    Ok(updates)
+}
+
+pub fn run(args: UpdateApp, client: &RuntipiClient, env_map: &EnvMap) {
+    let request = AppLifecycleRequest::Update(UpdateAppBody {
+        perform_backup: !args.no_backup,
+    });
+    let selector = &args.selector;
+
+    // Apps named explicitly are updated as requested, selections only pick the apps with a newer version
+    if !selector.is_selection() {
+        run_selected_action(client, &request, selector, &args.wait);
+        return;
+    }
+
+    let pattern = match selection_pattern(selector) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            println!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let updates = match available_updates(client, pattern.as_ref(), env_map) {
+        Ok(updates) => updates,
+        Err(err) => {
+            println!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if updates.is_empty() {
+        println!("{} All selected apps are up to date", "✓".green());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["App", "Installed", "Available"]);
+
+    for (id, update) in &updates {
+        table.add_row(row![
+            id,
+            format!("{} ({})", update.current_version, update.current_tipi_version),
+            format!("{} ({})", update.latest_version, update.latest_tipi_version).green()
+        ]);
+    }
+
+    println!("The following apps will be updated:");
+    table.printstd();
+
+    if !args.yes && !confirm(&format!("Update {} app(s)?", updates.len())) {
+        println!("Update cancelled");
+        return;
+    }
+
+    let ids = updates.iter().map(|(id, _)| id.clone()).collect::<Vec<String>>();
+
+    run_batch_action(client, &request, &ids, selector.parallel, &args.wait);
+}
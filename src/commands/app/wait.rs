@@ -0,0 +1,171 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::utils::api::{ApiError, RuntipiClient};
+use crate::utils::apps::{find_installed_app, mentions_app};
+use crate::utils::docker::get_compose_containers;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long the worker has to pick up a job before a stable state is trusted
+const PICKUP_GRACE_PERIOD: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetState {
+    Running,
+    Stopped,
+    Uninstalled,
+}
+
+impl fmt::Display for TargetState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetState::Running => write!(f, "running"),
+            TargetState::Stopped => write!(f, "stopped"),
+            TargetState::Uninstalled => write!(f, "uninstalled"),
+        }
+    }
+}
+
+/**
+* Remembers the size of `logs/error.log` when an operation is sent so only the errors
+* written by the worker for this operation are reported.
+*/
+pub struct ErrorLogCursor {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl ErrorLogCursor {
+    pub fn new() -> ErrorLogCursor {
+        let path = std::env::current_dir().unwrap_or_default().join("logs").join("error.log");
+        let offset = std::fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+
+        ErrorLogCursor { path, offset }
+    }
+
+    /// The lines written since the cursor was created that mention the app
+    pub fn new_errors(&self, app_id: &str) -> Vec<String> {
+        let mut content = String::new();
+        let read = File::open(&self.path).and_then(|mut file| {
+            file.seek(SeekFrom::Start(self.offset))?;
+            file.read_to_string(&mut content)
+        });
+
+        if read.is_err() {
+            return vec![];
+        }
+
+        content
+            .lines()
+            .filter(|line| mentions_app(line, app_id))
+            .map(|line| line.trim().to_string())
+            .collect()
+    }
+}
+
+fn is_transitional(status: &str) -> bool {
+    status.ends_with("ing") && status != "running" && status != "missing"
+}
+
+/// Current status as reported by the dashboard, `None` if the API is unreachable
+fn status_from_api(client: &RuntipiClient, app_id: &str) -> Option<String> {
+    let body = match client.app(app_id) {
+        Ok(body) => body,
+        Err(ApiError::Client {
+            status: StatusCode::NOT_FOUND,
+            ..
+        }) => return Some(TargetState::Uninstalled.to_string()),
+        Err(_) => return None,
+    };
+
+    match body.get("app") {
+        Some(Value::Null) => Some(TargetState::Uninstalled.to_string()),
+        _ => body
+            .pointer("/app/status")
+            .or(body.get("status"))
+            .and_then(|status| status.as_str())
+            .map(|status| status.to_string()),
+    }
+}
+
+/// Current status derived from the app folder and the health of its containers
+fn status_from_docker(app_id: &str) -> Option<String> {
+    let root_folder = std::env::current_dir().unwrap_or_default();
+
+    let app = match find_installed_app(&root_folder, app_id) {
+        Ok(app) => app,
+        Err(_) => return Some(TargetState::Uninstalled.to_string()),
+    };
+
+    let containers = get_compose_containers(Some(&app.compose_project())).ok()?;
+
+    let status = if containers.is_empty() || containers.iter().all(|container| !container.is_running()) {
+        "stopped"
+    } else if containers.iter().all(|container| container.is_ready()) {
+        "running"
+    } else {
+        "starting"
+    };
+
+    Some(status.to_string())
+}
+
+/**
+* Poll the app status until it reaches `target`. When `requires_transition` is set, the target state is only
+* trusted once the worker was seen working on the app or the pickup grace period is over, this avoids
+* returning early for operations like reset or update where the app is already running before the job starts.
+*/
+pub fn wait_for_state(
+    client: &RuntipiClient,
+    app_id: &str,
+    target: TargetState,
+    requires_transition: bool,
+    timeout: Duration,
+    error_log: &ErrorLogCursor,
+) -> Result<(), String> {
+    let started_at = Instant::now();
+    let mut seen_transition = false;
+
+    loop {
+        // The app folder and the containers of a remote instance are not on this machine, keep asking the API
+        let status = match client.is_remote() {
+            true => status_from_api(client, app_id),
+            false => status_from_api(client, app_id).or_else(|| status_from_docker(app_id)),
+        };
+
+        if let Some(status) = status {
+            let grace_period_over = started_at.elapsed() > PICKUP_GRACE_PERIOD;
+
+            if is_transitional(&status) {
+                seen_transition = true;
+            } else if status == target.to_string() {
+                if !requires_transition || seen_transition || grace_period_over {
+                    return Ok(());
+                }
+            } else if seen_transition || grace_period_over {
+                let errors = match client.is_remote() {
+                    true => vec![],
+                    false => error_log.new_errors(app_id),
+                };
+
+                return Err(match errors.last() {
+                    Some(error) => error.clone(),
+                    None => format!("App ended up {} instead of {}", status, target),
+                });
+            }
+        }
+
+        if started_at.elapsed() > timeout {
+            return Err(format!("Timed out after {}s waiting for the app to be {}", timeout.as_secs(), target));
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::args::{AddContext, ContextCommand, ContextSubcommand, RemoveContext, UseContext};
+use crate::components::spinner;
+use crate::utils::api::{ClientOptions, RuntipiClient};
+use crate::utils::context::{contexts_path, Context, ContextStore, DEFAULT_CONTEXT};
+use crate::utils::env::EnvMap;
+use crate::utils::repos::is_valid_repo_id;
+
+fn exit_with_error(message: &str) -> ! {
+    println!("{} {}", "✗".red(), message);
+    std::process::exit(1);
+}
+
+fn load_store(path: &Path) -> ContextStore {
+    ContextStore::load(path).unwrap_or_else(|err| exit_with_error(&format!("Unable to read {}: {}", path.display(), err)))
+}
+
+fn save_store(store: &ContextStore, path: &Path) {
+    if let Err(err) = store.save(path) {
+        exit_with_error(&format!("Unable to write {}: {}", path.display(), err));
+    }
+}
+
+/// The context selected with `--context` or `context use`, `None` for the instance in the current directory
+pub fn active_context(name: Option<&str>) -> Option<Context> {
+    let store = load_store(&contexts_path());
+
+    match store.resolve(name) {
+        Ok(context) => context.cloned(),
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+/// A client for the active context, or for the instance in the current directory
+pub fn create_client(context: Option<&Context>, env_map: &EnvMap, api_timeout: Option<u64>) -> RuntipiClient {
+    let client = match context {
+        // The settings of the local instance say nothing about a remote one
+        Some(context) => RuntipiClient::from_context(context, ClientOptions::from_env_map(&EnvMap::new(), api_timeout)),
+        None => RuntipiClient::from_env_map(env_map, ClientOptions::from_env_map(env_map, api_timeout)),
+    };
+
+    client.unwrap_or_else(|err| exit_with_error(&err.to_string()))
+}
+
+fn add(args: AddContext, path: &Path, api_timeout: Option<u64>) {
+    if args.name == DEFAULT_CONTEXT {
+        exit_with_error(&format!("'{}' is reserved for the instance in the current directory", DEFAULT_CONTEXT));
+    }
+
+    if !is_valid_repo_id(&args.name) {
+        exit_with_error(&format!("'{}' is not a valid context name, use letters, numbers, - and _", args.name));
+    }
+
+    let mut store = load_store(path);
+
+    if store.get(&args.name).is_some() {
+        exit_with_error(&format!("Context {} already exists, remove it first", args.name));
+    }
+
+    let ca_cert = args.ca_cert.map(|ca_cert| {
+        ca_cert
+            .canonicalize()
+            .unwrap_or_else(|err| exit_with_error(&format!("Unable to read {}: {}", ca_cert.display(), err)))
+            .to_string_lossy()
+            .to_string()
+    });
+
+    let context = Context {
+        name: args.name,
+        api_url: args.api_url,
+        jwt_secret: args.jwt_secret,
+        token: args.token,
+        ca_cert,
+    };
+
+    // Saved even when the instance is down, it may just be stopped
+    let spin = spinner::new(&format!("Checking {}...", context.api_url));
+    match create_client(Some(&context), &EnvMap::new(), api_timeout).health() {
+        Ok(_) => spin.succeed(&format!("Context {} added", context.name)),
+        Err(err) => spin.fail(&format!("Context {} added but the instance is unreachable: {}", context.name, err)),
+    }
+    spin.finish();
+
+    store.contexts.push(context);
+    save_store(&store, path);
+}
+
+fn use_context(args: UseContext, path: &Path) {
+    let mut store = load_store(path);
+
+    if args.name != DEFAULT_CONTEXT && store.get(&args.name).is_none() {
+        exit_with_error(&format!("Context {} not found, see `runtipi-cli context list`", args.name));
+    }
+
+    store.current = match args.name.as_str() {
+        DEFAULT_CONTEXT => None,
+        _ => Some(args.name.clone()),
+    };
+    save_store(&store, path);
+
+    println!("{} Using context {}", "✓".green(), args.name);
+}
+
+fn list(path: &Path) {
+    let store = load_store(path);
+    let current = store.current.as_deref().unwrap_or(DEFAULT_CONTEXT);
+    let marker = |name: &str| if name == current { "*".green() } else { "".normal() };
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["", "Name", "API URL", "Auth"]);
+    table.add_row(row![
+        marker(DEFAULT_CONTEXT),
+        DEFAULT_CONTEXT,
+        "Current directory",
+        "JWT_SECRET from .env"
+    ]);
+
+    for context in &store.contexts {
+        let auth = match (&context.jwt_secret, &context.token) {
+            (Some(_), _) => "JWT secret",
+            (None, Some(_)) => "Token",
+            (None, None) => "None",
+        };
+
+        table.add_row(row![marker(&context.name), context.name, context.api_url, auth]);
+    }
+
+    table.printstd();
+}
+
+fn remove(args: RemoveContext, path: &Path) {
+    let mut store = load_store(path);
+
+    if store.get(&args.name).is_none() {
+        exit_with_error(&format!("Context {} not found", args.name));
+    }
+
+    store.contexts.retain(|context| context.name != args.name);
+
+    if store.current.as_deref() == Some(args.name.as_str()) {
+        store.current = None;
+        println!("{} Context {} was in use, switched back to {}", "⚠".yellow(), args.name, DEFAULT_CONTEXT);
+    }
+
+    save_store(&store, path);
+
+    println!("{} Context {} removed", "✓".green(), args.name);
+}
+
+pub fn run(args: ContextCommand, api_timeout: Option<u64>) {
+    let path = contexts_path();
+
+    match args.subcommand {
+        ContextSubcommand::Add(args) => add(args, &path, api_timeout),
+        ContextSubcommand::Use(args) => use_context(args, &path),
+        ContextSubcommand::List => list(&path),
+        ContextSubcommand::Remove(args) => remove(args, &path),
+    }
+}
@@ -3,9 +3,40 @@ use colored::Colorize;
 use prettytable::{format, row, Table};
 use serde_json::{to_string_pretty, Value};
 
-use crate::utils::{env::EnvMap, system::get_architecture};
+use crate::commands::status::add_dashboard_rows;
+use crate::utils::api::{ClientOptions, RuntipiClient};
+use crate::utils::env::{is_secret_key, EnvMap};
+use crate::utils::system::get_architecture;
 
-pub fn run(env_map: EnvMap) {
+fn print_dashboard(env_map: &EnvMap, api_timeout: Option<u64>) {
+    println!("\n--- {} ---", "Dashboard".blue());
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    let client = match RuntipiClient::from_env_map(env_map, ClientOptions::from_env_map(env_map, api_timeout)) {
+        Ok(client) => client,
+        Err(err) => {
+            table.add_row(row!["API", err.to_string().red()]);
+            table.printstd();
+            return;
+        }
+    };
+
+    add_dashboard_rows(&client, &mut table);
+
+    // The dashboard only picks up settings.json changes on restart
+    if let Ok(settings) = client.settings() {
+        let not_set = || "Not set".to_string();
+        table.add_row(row!["Listen IP", settings.internal_ip.unwrap_or_else(not_set)]);
+        table.add_row(row!["Port", settings.nginx_port.map(|port| port.as_string()).unwrap_or_else(not_set)]);
+        table.add_row(row!["Domain", settings.domain.map(|_| "<redacted>".to_string()).unwrap_or_else(not_set)]);
+        table.add_row(row!["Local domain", settings.local_domain.unwrap_or_else(not_set)]);
+    }
+
+    table.printstd();
+}
+
+pub fn run(env_map: EnvMap, api_timeout: Option<u64>) {
     println!("⚠️ Make sure you have started tipi before running this command\n");
     // Gather system information
     let os = std::env::consts::OS;
@@ -58,65 +89,45 @@ pub fn run(env_map: EnvMap) {
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_BOX_CHARS);
 
-    let pg_password = env_map
-        .get("POSTGRES_PASSWORD")
-        .map(|_| "<redacted>".to_string())
-        .unwrap_or("Not set".red().to_string());
-    let rabbitmq_password = env_map
-        .get("RABBITMQ_PASSWORD")
-        .map(|_| "<redacted>".to_string())
-        .unwrap_or("Not set".red().to_string());
-    let jwt_secret = env_map
-        .get("JWT_SECRET")
-        .map(|_| "<redacted>".to_string())
-        .unwrap_or("Not set".red().to_string());
-    let domain = env_map.get("DOMAIN").map(|_| "<redacted>").unwrap_or("Not set");
-
-    table.add_row(row!["POSTGRES_PASSWORD", pg_password]);
-    table.add_row(row!["RABBITMQ_PASSWORD", rabbitmq_password]);
-    table.add_row(row!["APPS_REPO_ID", env_map.get("APPS_REPO_ID").unwrap_or(&"Not set".red().to_string())]);
-    table.add_row(row![
-        "APPS_REPO_URL",
-        env_map.get("APPS_REPO_URL").unwrap_or(&"Not set".red().to_string())
-    ]);
-    table.add_row(row!["TIPI_VERSION", env_map.get("TIPI_VERSION").unwrap_or(&"Not set".red().to_string())]);
-    table.add_row(row!["INTERNAL_IP", env_map.get("INTERNAL_IP").unwrap_or(&"Not set".red().to_string())]);
-    table.add_row(row!["ARCHITECTURE", env_map.get("ARCHITECTURE").unwrap_or(&"Not set".red().to_string())]);
-    table.add_row(row!["JWT_SECRET", jwt_secret]);
-    table.add_row(row![
-        "ROOT_FOLDER_HOST",
-        env_map.get("ROOT_FOLDER_HOST").unwrap_or(&"Not set".red().to_string())
-    ]);
-    table.add_row(row![
-        "RUNTIPI_APP_DATA_PATH",
-        env_map.get("RUNTIPI_APP_DATA_PATH").unwrap_or(&"Not set".red().to_string())
-    ]);
-    table.add_row(row!["NGINX_PORT", env_map.get("NGINX_PORT").unwrap_or(&"Not set".red().to_string())]);
-    table.add_row(row![
-        "NGINX_PORT_SSL",
-        env_map.get("NGINX_PORT_SSL").unwrap_or(&"Not set".red().to_string())
-    ]);
-    table.add_row(row!["DOMAIN", domain]);
-    table.add_row(row!["POSTGRES_HOST", env_map.get("POSTGRES_HOST").unwrap_or(&"Not set".to_string())]);
-    table.add_row(row!["POSTGRES_DBNAME", env_map.get("POSTGRES_DBNAME").unwrap_or(&"Not set".to_string())]);
-    table.add_row(row![
-        "POSTGRES_USERNAME",
-        env_map.get("POSTGRES_USERNAME").unwrap_or(&"Not set".to_string())
-    ]);
-    table.add_row(row![
-        "POSTGRES_PORT",
-        env_map.get("POSTGRES_PORT").unwrap_or(&"Not set".red().to_string())
-    ]);
-    table.add_row(row!["RABBITMQ_HOST", env_map.get("RABBITMQ_HOST").unwrap_or(&"Not set".to_string())]);
-    table.add_row(row![
-        "RABBITMQ_USERNAME",
-        env_map.get("RABBITMQ_USERNAME").unwrap_or(&"Not set".to_string())
-    ]);
-    table.add_row(row!["DEMO_MODE", env_map.get("DEMO_MODE").unwrap_or(&"Not set".to_string())]);
-    table.add_row(row!["LOCAL_DOMAIN", env_map.get("LOCAL_DOMAIN").unwrap_or(&"Not set".to_string())]);
+    // The variables to show and whether runtipi needs them to start
+    let variables = [
+        ("POSTGRES_PASSWORD", true),
+        ("RABBITMQ_PASSWORD", true),
+        ("APPS_REPO_ID", true),
+        ("APPS_REPO_URL", true),
+        ("TIPI_VERSION", true),
+        ("INTERNAL_IP", true),
+        ("ARCHITECTURE", true),
+        ("JWT_SECRET", true),
+        ("ROOT_FOLDER_HOST", true),
+        ("RUNTIPI_APP_DATA_PATH", true),
+        ("NGINX_PORT", true),
+        ("NGINX_PORT_SSL", true),
+        ("DOMAIN", false),
+        ("POSTGRES_HOST", false),
+        ("POSTGRES_DBNAME", false),
+        ("POSTGRES_USERNAME", false),
+        ("POSTGRES_PORT", true),
+        ("RABBITMQ_HOST", false),
+        ("RABBITMQ_USERNAME", false),
+        ("DEMO_MODE", false),
+        ("LOCAL_DOMAIN", false),
+    ];
+
+    for (key, required) in variables {
+        let value = match env_map.get(key) {
+            Some(_) if is_secret_key(key) => "<redacted>".to_string(),
+            Some(value) => value.clone(),
+            None if required => "Not set".red().to_string(),
+            None => "Not set".to_string(),
+        };
+        table.add_row(row![key, value]);
+    }
 
     table.printstd();
 
+    print_dashboard(&env_map, api_timeout);
+
     println!("\n--- {} ---", "Docker containers".blue());
     table = Table::new();
     table.set_format(*format::consts::FORMAT_BOX_CHARS);
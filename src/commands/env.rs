@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::args::{EnvCommand, EnvSubcommand};
+use crate::components::diff::print_diff;
+use crate::utils::dotenv::{self, is_valid_key, EnvDocument};
+use crate::utils::env::{is_secret_key, read_generated_snapshot, render_env_file, user_env_file_path};
+use crate::utils::form::parse_key_value;
+use crate::utils::validation::validate_env_value;
+
+/// Computed from the host on every start, an override would be wrong as soon as the host changes
+const COMPUTED_KEYS: &[&str] = &["ARCHITECTURE", "TIPI_VERSION", "ROOT_FOLDER_HOST"];
+
+fn exit_with_error(message: &str) -> ! {
+    println!("{} {}", "✗".red(), message);
+    std::process::exit(1);
+}
+
+/// Hide the value of secret entries, a secret in `changed` is marked so that a diff still shows it
+fn redact(content: &str, changed: &HashSet<String>) -> String {
+    EnvDocument::parse(content)
+        .redacted(|key| match (is_secret_key(key), changed.contains(key)) {
+            (false, _) => None,
+            (true, true) => Some("<redacted, changed>".to_string()),
+            (true, false) => Some("<redacted>".to_string()),
+        })
+        .to_string()
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_default()
+}
+
+fn write(path: &Path, content: &str) {
+    let written = path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(path, content));
+
+    match written {
+        Ok(_) => {
+            println!("{} Saved {}", "✓".green(), path.display());
+            println!("Restart runtipi to apply the changes: runtipi-cli restart");
+        }
+        Err(err) => exit_with_error(&format!("Unable to write {}: {}", path.display(), err)),
+    }
+}
+
+fn get(root_folder: &Path, key: &str) {
+    match EnvDocument::parse(&read(&root_folder.join(".env"))).get(key) {
+        Some(value) => println!("{}", value),
+        None => exit_with_error(&format!("{} is not set in .env", key)),
+    }
+}
+
+fn set(root_folder: &Path, values: &[String]) {
+    let path = user_env_file_path(root_folder);
+    let mut document = EnvDocument::parse(&read(&path));
+
+    for raw in values {
+        let (key, value) = parse_key_value(raw).unwrap_or_else(|err| exit_with_error(&err));
+
+        if !is_valid_key(&key) {
+            exit_with_error(&format!("'{}' is not a valid variable name", key));
+        }
+        if COMPUTED_KEYS.contains(&key.as_str()) {
+            exit_with_error(&format!("{} is computed on every start and can't be set", key));
+        }
+        if let Err(err) = validate_env_value(&key, &value) {
+            exit_with_error(&format!("Invalid value for {}: {}", key, err));
+        }
+
+        document.set(&key, &value);
+    }
+
+    write(&path, &document.to_string());
+}
+
+fn unset(root_folder: &Path, keys: &[String]) {
+    let path = user_env_file_path(root_folder);
+    let mut document = EnvDocument::parse(&read(&path));
+
+    for key in keys {
+        if document.get(key).is_none() {
+            exit_with_error(&format!("{} is not set in {}", key, path.display()));
+        }
+        document.remove(key);
+    }
+
+    write(&path, &document.to_string());
+}
+
+fn list(root_folder: &Path, show_secrets: bool) {
+    let (entries, _) = dotenv::parse_lenient(&read(&root_folder.join(".env")));
+    let user_keys = dotenv::parse_lenient(&read(&user_env_file_path(root_folder)))
+        .0
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect::<HashSet<String>>();
+    let generated = read_generated_snapshot(root_folder);
+
+    // Compose uses the last value of a duplicated key
+    let values = entries.iter().cloned().collect::<BTreeMap<String, String>>();
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["Key", "Value", "Source"]);
+
+    let mut seen = HashSet::new();
+    for (key, _) in &entries {
+        if !seen.insert(key) {
+            continue;
+        }
+
+        let value = match is_secret_key(key) && !show_secrets {
+            true => "<redacted>".to_string(),
+            false => values[key].clone(),
+        };
+        let source = if user_keys.contains(key) {
+            "user-config/.env".normal()
+        } else if generated.contains_key(key) {
+            "generated".normal()
+        } else {
+            ".env".normal()
+        };
+
+        table.add_row(row![key, value, source]);
+    }
+
+    table.printstd();
+}
+
+fn diff(root_folder: &Path, show_secrets: bool, env_file: Option<&Path>) {
+    let rendered = render_env_file(root_folder, env_file).unwrap_or_else(|err| exit_with_error(&err.to_string()));
+
+    for warning in &rendered.warnings {
+        println!("{} {}", "⚠".yellow(), warning);
+    }
+
+    let (old, new) = if show_secrets {
+        (rendered.current, rendered.content)
+    } else {
+        let before = dotenv::parse_lenient(&rendered.current)
+            .0
+            .into_iter()
+            .collect::<BTreeMap<String, String>>();
+        let changed = dotenv::parse_lenient(&rendered.content)
+            .0
+            .into_iter()
+            .filter(|(key, value)| before.get(key) != Some(value))
+            .map(|(key, _)| key)
+            .collect::<HashSet<String>>();
+
+        (redact(&rendered.current, &HashSet::new()), redact(&rendered.content, &changed))
+    };
+
+    if !print_diff(&old, &new) {
+        println!("{} .env is up to date", "✓".green());
+    }
+}
+
+pub fn run(args: EnvCommand) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    match args.subcommand {
+        EnvSubcommand::Get { key } => get(&root_folder, &key),
+        EnvSubcommand::Set { values } => set(&root_folder, &values),
+        EnvSubcommand::Unset { keys } => unset(&root_folder, &keys),
+        EnvSubcommand::List { show_secrets } => list(&root_folder, show_secrets),
+        EnvSubcommand::Diff { show_secrets, env_file } => diff(&root_folder, show_secrets, env_file.as_deref()),
+    }
+}
@@ -1,8 +1,14 @@
 pub mod app;
+pub mod context;
 pub mod debug;
+pub mod env;
+pub mod repo;
 pub mod reset_password;
+pub mod settings;
 pub mod start;
+pub mod status;
 pub mod stop;
+pub mod store;
 pub mod update;
 
 pub mod version;
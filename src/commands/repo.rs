@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::args::{AddRepo, RemoveRepo, RepoCommand, RepoSubcommand, UpdateRepo};
+use crate::components::spinner;
+use crate::utils::apps::{folder_name, get_installed_apps, sorted_sub_folders};
+use crate::utils::repos::{
+    get_configured_repos, git_clone, git_head, git_pull, git_remote_url, is_valid_repo_id, repo_id_from_url, save_configured_repos,
+};
+use crate::utils::schemas::AppStoreRepo;
+use crate::utils::store::get_store_apps;
+
+struct KnownRepo {
+    id: String,
+    url: Option<String>,
+    branch: Option<String>,
+    /// Repositories cloned by the dashboard are not in settings.json
+    configured: bool,
+}
+
+/// The repositories of settings.json followed by the other clones found in repos/
+fn known_repos(root_folder: &Path) -> Vec<KnownRepo> {
+    let configured = get_configured_repos(root_folder).unwrap_or_else(|err| {
+        println!("{} Unable to read the repositories of settings.json: {}", "✗".red(), err);
+        std::process::exit(1);
+    });
+
+    let mut repos = configured
+        .into_iter()
+        .map(|repo| KnownRepo {
+            id: repo.id,
+            url: Some(repo.url),
+            branch: repo.branch,
+            configured: true,
+        })
+        .collect::<Vec<KnownRepo>>();
+
+    for folder in sorted_sub_folders(&root_folder.join("repos")).unwrap_or_default() {
+        let id = folder_name(&folder);
+
+        if !repos.iter().any(|repo| repo.id == id) {
+            repos.push(KnownRepo {
+                id,
+                url: git_remote_url(&folder).ok(),
+                branch: None,
+                configured: false,
+            });
+        }
+    }
+
+    repos
+}
+
+fn app_counts(root_folder: &Path) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+
+    for app in get_store_apps(root_folder).unwrap_or_default() {
+        *counts.entry(app.store).or_default() += 1;
+    }
+
+    counts
+}
+
+fn repo_path(root_folder: &Path, id: &str) -> PathBuf {
+    root_folder.join("repos").join(id)
+}
+
+fn exit_with_error(message: &str) -> ! {
+    println!("{} {}", "✗".red(), message);
+    std::process::exit(1);
+}
+
+fn list(root_folder: &Path) {
+    let repos = known_repos(root_folder);
+
+    if repos.is_empty() {
+        println!("No app store repositories found. Add one with: runtipi-cli repo add <url>");
+        return;
+    }
+
+    let counts = app_counts(root_folder);
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["ID", "URL", "Branch", "Commit", "Apps", "In settings.json"]);
+
+    for repo in repos {
+        let commit = match git_head(&repo_path(root_folder, &repo.id)) {
+            Ok(commit) => commit.normal(),
+            Err(_) => "Not cloned".red(),
+        };
+
+        table.add_row(row![
+            repo.id,
+            repo.url.unwrap_or("-".to_string()),
+            repo.branch.unwrap_or("-".to_string()),
+            commit,
+            counts.get(&repo.id).unwrap_or(&0),
+            if repo.configured { "Yes".normal() } else { "No".bright_white() }
+        ]);
+    }
+
+    table.printstd();
+}
+
+fn add(args: AddRepo, root_folder: &Path) {
+    let id = args.id.unwrap_or(repo_id_from_url(&args.url));
+
+    if !is_valid_repo_id(&id) {
+        exit_with_error(&format!(
+            "'{}' is not a valid repository id, use letters, numbers, - and _ or set one with --id",
+            id
+        ));
+    }
+
+    let mut configured = get_configured_repos(root_folder).unwrap_or_else(|err| {
+        exit_with_error(&format!("Unable to read the repositories of settings.json: {}", err));
+    });
+
+    if let Some(existing) = configured.iter().find(|repo| repo.id == id || repo.url == args.url) {
+        exit_with_error(&format!("Repository {} ({}) is already configured", existing.id, existing.url));
+    }
+
+    let path = repo_path(root_folder, &id);
+
+    if path.exists() {
+        exit_with_error(&format!("{} already exists, pick another id with --id", path.display()));
+    }
+
+    let spin = spinner::new(&format!("Cloning {}...", args.url));
+
+    if let Err(err) = git_clone(&args.url, args.branch.as_deref(), &path) {
+        spin.fail(&format!("Failed to clone {}", args.url));
+        spin.finish();
+        println!("Error: {}", err);
+        std::process::exit(1);
+    }
+
+    configured.push(AppStoreRepo {
+        id: id.clone(),
+        url: args.url,
+        branch: args.branch,
+    });
+
+    if let Err(err) = save_configured_repos(root_folder, &configured) {
+        spin.fail("Failed to save settings.json");
+        spin.finish();
+        println!("Error: {}", err);
+        std::process::exit(1);
+    }
+
+    let commit = git_head(&path).unwrap_or_default();
+    let apps = app_counts(root_folder).get(&id).copied().unwrap_or(0);
+    spin.succeed(&format!("Repository {} added at commit {} with {} apps", id, commit, apps));
+    spin.finish();
+}
+
+fn remove(args: RemoveRepo, root_folder: &Path) {
+    let mut configured = get_configured_repos(root_folder).unwrap_or_else(|err| {
+        exit_with_error(&format!("Unable to read the repositories of settings.json: {}", err));
+    });
+    let path = repo_path(root_folder, &args.id);
+
+    if !configured.iter().any(|repo| repo.id == args.id) && !path.exists() {
+        exit_with_error(&format!("Repository {} not found", args.id));
+    }
+
+    let installed = get_installed_apps(root_folder)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|app| app.store.as_deref() == Some(args.id.as_str()))
+        .map(|app| app.id)
+        .collect::<Vec<String>>();
+
+    if !installed.is_empty() && !args.force {
+        exit_with_error(&format!(
+            "Apps are installed from repository {}: {}. Uninstall them first or use --force",
+            args.id,
+            installed.join(", ")
+        ));
+    }
+
+    let spin = spinner::new(&format!("Removing repository {}...", args.id));
+
+    configured.retain(|repo| repo.id != args.id);
+
+    let removed = save_configured_repos(root_folder, &configured).and_then(|_| match path.exists() {
+        true => fs::remove_dir_all(&path),
+        false => Ok(()),
+    });
+
+    match removed {
+        Ok(_) => spin.succeed(&format!("Repository {} removed", args.id)),
+        Err(err) => {
+            spin.fail(&format!("Failed to remove repository {}", args.id));
+            spin.finish();
+            println!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+    spin.finish();
+}
+
+fn update(args: UpdateRepo, root_folder: &Path) {
+    let repos = known_repos(root_folder)
+        .into_iter()
+        .filter(|repo| args.id.as_ref().is_none_or(|id| *id == repo.id))
+        .collect::<Vec<KnownRepo>>();
+
+    if repos.is_empty() {
+        match args.id {
+            Some(id) => exit_with_error(&format!("Repository {} not found", id)),
+            None => {
+                println!("No app store repositories found. Add one with: runtipi-cli repo add <url>");
+                return;
+            }
+        }
+    }
+
+    let mut failed = false;
+
+    for repo in repos {
+        let path = repo_path(root_folder, &repo.id);
+        let spin = spinner::new(&format!("Updating repository {}...", repo.id));
+        let previous = git_head(&path).ok();
+
+        let result = match (&previous, &repo.url) {
+            (Some(_), _) => git_pull(&path),
+            // Configured but never cloned, or the clone was deleted
+            (None, Some(url)) => git_clone(url, repo.branch.as_deref(), &path),
+            (None, None) => Err(std::io::Error::other(format!("{} is not a git repository", path.display()))),
+        };
+
+        match result.and_then(|_| git_head(&path)) {
+            Ok(commit) => {
+                let apps = app_counts(root_folder).get(&repo.id).copied().unwrap_or(0);
+                let change = match previous {
+                    Some(previous) if previous == commit => format!("already up to date at {}", commit),
+                    Some(previous) => format!("updated from {} to {}", previous, commit),
+                    None => format!("cloned at {}", commit),
+                };
+                spin.succeed(&format!("Repository {} {} with {} apps", repo.id, change, apps));
+            }
+            Err(err) => {
+                failed = true;
+                spin.fail(&format!("Failed to update repository {}: {}", repo.id, err));
+            }
+        }
+        spin.finish();
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+pub fn run(args: RepoCommand) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    match args.subcommand {
+        RepoSubcommand::List => list(&root_folder),
+        RepoSubcommand::Add(args) => add(args, &root_folder),
+        RepoSubcommand::Remove(args) => remove(args, &root_folder),
+        RepoSubcommand::Update(args) => update(args, &root_folder),
+    }
+}
@@ -0,0 +1,46 @@
+use std::env;
+
+use colored::Colorize;
+
+use crate::args::{SettingsCommand, SettingsSubcommand};
+use crate::utils::settings::{validate_settings, Severity};
+
+fn validate() {
+    let path = env::current_dir()
+        .expect("Unable to get current directory")
+        .join("state")
+        .join("settings.json");
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) => {
+            println!("{} Unable to read {}: {}", "✗".red(), path.display(), err);
+            std::process::exit(1);
+        }
+    };
+
+    let problems = validate_settings(&content);
+
+    if problems.is_empty() {
+        println!("{} {} is valid", "✓".green(), path.display());
+        return;
+    }
+
+    println!("Found {} problem(s) in {}:", problems.len(), path.display());
+    for problem in &problems {
+        match problem.severity {
+            Severity::Error => println!("  {} {}", "✗".red(), problem),
+            Severity::Warning => println!("  {} {}", "⚠".yellow(), problem),
+        }
+    }
+
+    if problems.iter().any(|problem| problem.severity == Severity::Error) {
+        std::process::exit(1);
+    }
+}
+
+pub fn run(args: SettingsCommand) {
+    match args.subcommand {
+        SettingsSubcommand::Validate => validate(),
+    }
+}
@@ -35,14 +35,21 @@ pub fn run(args: StartArgs) {
     // Env file generation
     spin.set_message("Generating .env file...");
 
-    if let Err(e) = env::generate_env_file(args.env_file) {
-        spin.fail("Failed to generate .env file");
-        spin.finish();
-        println!("\nError: {}", e);
-        return;
-    }
+    match env::generate_env_file(args.env_file) {
+        Ok(warnings) => {
+            spin.succeed("Generated .env file");
 
-    spin.succeed("Generated .env file");
+            for warning in warnings {
+                spin.warn(&warning);
+            }
+        }
+        Err(e) => {
+            spin.fail("Failed to generate .env file");
+            spin.finish();
+            println!("\nError: {}", e);
+            return;
+        }
+    }
 
     spin.set_message("Ensuring file permissions... This may take a while depending on how many files there are to fix");
 
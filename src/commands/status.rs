@@ -0,0 +1,66 @@
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::commands::context::create_client;
+use crate::utils::api::RuntipiClient;
+use crate::utils::context::{Context, DEFAULT_CONTEXT};
+use crate::utils::env::EnvMap;
+use crate::utils::schemas::ResourceUsage;
+
+fn format_usage(usage: &ResourceUsage) -> String {
+    let gb = |bytes: f64| bytes / 1024.0 / 1024.0 / 1024.0;
+
+    match (usage.used, usage.total) {
+        (Some(used), Some(total)) => format!("{:.2} / {:.2} GB", gb(used), gb(total)),
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Health and resource usage as reported by the dashboard, shared with `debug`
+pub fn add_dashboard_rows(client: &RuntipiClient, table: &mut Table) {
+    match client.health() {
+        Ok(_) => table.add_row(row!["Health", "OK".green()]),
+        Err(err) => table.add_row(row!["Health", err.to_string().red()]),
+    };
+
+    if let Ok(load) = client.system_load() {
+        let cpu = load.cpu.load.map(|load| format!("{:.1}%", load)).unwrap_or("Unknown".to_string());
+        table.add_row(row!["CPU load", cpu]);
+        table.add_row(row!["Memory", format_usage(&load.memory)]);
+        table.add_row(row!["Disk", format_usage(&load.disk)]);
+    }
+}
+
+pub fn run(context: Option<Context>, env_map: EnvMap, api_timeout: Option<u64>) {
+    let client = create_client(context.as_ref(), &env_map, api_timeout);
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+
+    match &context {
+        Some(context) => {
+            table.add_row(row!["Context", context.name]);
+            table.add_row(row!["API URL", context.api_url]);
+        }
+        None => {
+            table.add_row(row!["Context", format!("{} (current directory)", DEFAULT_CONTEXT)]);
+        }
+    }
+
+    add_dashboard_rows(&client, &mut table);
+
+    if let Ok(version) = client.version() {
+        let latest = match version.latest {
+            Some(latest) if latest != version.current => format!("{} ({} available)", version.current, latest.yellow()),
+            _ => version.current,
+        };
+        table.add_row(row!["Version", latest]);
+    }
+
+    if let Ok(response) = client.installed_apps() {
+        let running = response.installed.iter().filter(|app| app.app.status == "running").count();
+        table.add_row(row!["Apps", format!("{} running / {} installed", running, response.installed.len())]);
+    }
+
+    table.printstd();
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use colored::Colorize;
+use prettytable::{format, row, Table};
+
+use crate::args::{SearchStore, ShowStoreApp, StoreCommand, StoreSubcommand};
+use crate::utils::apps::{get_installed_apps, InstalledApp};
+use crate::utils::env::EnvMap;
+use crate::utils::store::{find_store_app, get_store_apps, search_store_apps, StoreApp};
+
+fn installed_label(app: &StoreApp, installed_apps: &[InstalledApp]) -> colored::ColoredString {
+    if app.is_installed(installed_apps) {
+        "Installed".green()
+    } else {
+        "-".normal()
+    }
+}
+
+fn search(args: SearchStore, apps: &[StoreApp], installed_apps: &[InstalledApp]) {
+    let results = search_store_apps(apps, &args.term)
+        .into_iter()
+        .filter(|app| {
+            args.category.as_ref().is_none_or(|category| {
+                app.config
+                    .categories
+                    .iter()
+                    .any(|app_category| app_category.eq_ignore_ascii_case(category))
+            })
+        })
+        .collect::<Vec<&StoreApp>>();
+
+    if results.is_empty() {
+        println!("No apps match '{}'", args.term);
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["ID", "Name", "Version", "Description", "Installed"]);
+
+    for app in results {
+        table.add_row(row![
+            app.id(),
+            app.config.name,
+            app.config.version,
+            app.config.short_desc,
+            installed_label(app, installed_apps)
+        ]);
+    }
+
+    table.printstd();
+}
+
+fn show(args: ShowStoreApp, apps: &[StoreApp], installed_apps: &[InstalledApp], env_map: &EnvMap) {
+    let app = match find_store_app(apps, &args.id, env_map.get("APPS_REPO_ID").map(|s| s.as_str())) {
+        Ok(app) => app,
+        Err(err) => {
+            println!("{} {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    };
+
+    let not_set = || "-".to_string();
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.add_row(row!["ID", app.id()]);
+    table.add_row(row!["Name", app.config.name]);
+    table.add_row(row!["Store", app.store]);
+    table.add_row(row!["Version", app.config.version]);
+    table.add_row(row!["Description", app.config.short_desc]);
+    table.add_row(row!["Author", app.config.author.clone().unwrap_or_else(not_set)]);
+    table.add_row(row!["Source", app.config.source.clone().unwrap_or_else(not_set)]);
+    table.add_row(row!["Categories", app.config.categories.join(", ")]);
+    table.add_row(row!["Port", app.config.port.map(|port| port.to_string()).unwrap_or_else(not_set)]);
+    table.add_row(row!["Exposable", if app.config.exposable { "Yes" } else { "No" }]);
+    table.add_row(row!["Installed", installed_label(app, installed_apps)]);
+    table.printstd();
+
+    let description = app.description();
+    if !description.trim().is_empty() {
+        println!("\n{}", description.trim());
+    }
+
+    if !app.is_installed(installed_apps) {
+        println!("\nInstall it with: runtipi-cli app install {}", app.id());
+    }
+}
+
+fn categories(apps: &[StoreApp]) {
+    let mut counts = BTreeMap::<String, usize>::new();
+
+    for app in apps {
+        for category in &app.config.categories {
+            *counts.entry(category.to_lowercase()).or_default() += 1;
+        }
+    }
+
+    if counts.is_empty() {
+        println!("No categories found");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_BOX_CHARS);
+    table.set_titles(row!["Category", "Apps"]);
+
+    for (category, count) in counts {
+        table.add_row(row![category, count]);
+    }
+
+    table.printstd();
+}
+
+pub fn run(args: StoreCommand, env_map: EnvMap) {
+    let root_folder = env::current_dir().expect("Unable to get current directory");
+
+    let apps = match get_store_apps(&root_folder) {
+        Ok(apps) => apps,
+        Err(err) => {
+            println!("{} Failed to read the app store repositories: {}", "✗".red(), err);
+            std::process::exit(1);
+        }
+    };
+
+    if apps.is_empty() {
+        println!(
+            "{} No apps found in {}. Start runtipi to clone the app store repositories",
+            "✗".red(),
+            root_folder.join("repos").display()
+        );
+        std::process::exit(1);
+    }
+
+    let installed_apps = get_installed_apps(&root_folder).unwrap_or_default();
+
+    match args.subcommand {
+        StoreSubcommand::Search(args) => search(args, &apps, &installed_apps),
+        StoreSubcommand::Show(args) => show(args, &apps, &installed_apps, &env_map),
+        StoreSubcommand::Categories => categories(&apps),
+    }
+}
@@ -1,6 +1,20 @@
+use crate::commands::context::create_client;
+use crate::utils::context::Context;
 use crate::utils::env::EnvMap;
 
-pub fn run(env_map: EnvMap) {
+pub fn run(context: Option<Context>, env_map: EnvMap, api_timeout: Option<u64>) {
+    // A remote instance can only tell its version through the API
+    if let Some(context) = context {
+        match create_client(Some(&context), &env_map, api_timeout).version() {
+            Ok(version) => println!("{}", version.current),
+            Err(err) => {
+                println!("Unable to get the version of {}: {}", context.name, err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let version = env_map.get("TIPI_VERSION");
 
     match version {
@@ -0,0 +1,24 @@
+use colored::Colorize;
+use similar::{ChangeTag, TextDiff};
+
+/// Print a line diff between two versions of a file, returns false when they are identical
+pub fn print_diff(old: &str, new: &str) -> bool {
+    if old == new {
+        return false;
+    }
+
+    let diff = TextDiff::from_lines(old, new);
+
+    for change in diff.iter_all_changes() {
+        let line = change.to_string_lossy();
+        let line = line.trim_end_matches('\n');
+
+        match change.tag() {
+            ChangeTag::Delete => println!("{}", format!("- {}", line).red()),
+            ChangeTag::Insert => println!("{}", format!("+ {}", line).green()),
+            ChangeTag::Equal => println!("  {}", line),
+        }
+    }
+
+    true
+}
@@ -1,2 +1,4 @@
 pub mod console_box;
+pub mod diff;
+pub mod prompt;
 pub mod spinner;
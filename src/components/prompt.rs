@@ -0,0 +1,14 @@
+use std::io::{stdin, stdout, Write};
+
+/// Ask a yes/no question on the terminal, anything but `y` or `yes` counts as a no
+pub fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    let _ = stdout().flush();
+
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
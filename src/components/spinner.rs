@@ -1,14 +1,17 @@
 use std::time::Duration;
 
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
 pub struct CustomSpinner {
     spinner: ProgressBar,
 }
 
 impl CustomSpinner {
-    fn new(initial_message: String) -> CustomSpinner {
-        let spinner = ProgressBar::new_spinner();
+    fn new(initial_message: String, multi: Option<&MultiProgress>) -> CustomSpinner {
+        let spinner = match multi {
+            Some(multi) => multi.add(ProgressBar::new_spinner()),
+            None => ProgressBar::new_spinner(),
+        };
         let style: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
         let progress_style = ProgressStyle::default_spinner();
 
@@ -36,13 +39,13 @@ impl CustomSpinner {
         self.spinner.println(failure_message);
     }
 
-    // pub fn warn(&self, message: &str) {
-    //     const WARNING: &str = "\u{001b}[33;1m\u{26A0}\u{001b}[0m";
+    pub fn warn(&self, message: &str) {
+        const WARNING: &str = "\u{001b}[33;1m\u{26A0}\u{001b}[0m";
 
-    //     let warning_message = format!("{} {}", WARNING, message);
+        let warning_message = format!("{} {}", WARNING, message);
 
-    //     self.spinner.println(warning_message);
-    // }
+        self.spinner.println(warning_message);
+    }
 
     pub fn set_message(&self, message: &str) {
         self.spinner.set_message(message.to_string());
@@ -54,5 +57,10 @@ impl CustomSpinner {
 }
 
 pub fn new(message: &str) -> CustomSpinner {
-    CustomSpinner::new(message.to_string())
+    CustomSpinner::new(message.to_string(), None)
+}
+
+/// Create a spinner that renders on its own line alongside the other spinners of `multi`
+pub fn new_in(multi: &MultiProgress, message: &str) -> CustomSpinner {
+    CustomSpinner::new(message.to_string(), Some(multi))
 }
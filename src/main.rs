@@ -39,17 +39,44 @@ fn main() {
         args::RuntipiMainCommand::App(app_command) => {
             let env_map = get_env_map();
 
-            commands::app::run(app_command, env_map);
+            let context = commands::context::active_context(args.context.as_deref());
+
+            commands::app::run(app_command, context, env_map, args.timeout);
+        }
+        args::RuntipiMainCommand::Store(store_command) => {
+            let env_map = get_env_map();
+
+            commands::store::run(store_command, env_map);
+        }
+        args::RuntipiMainCommand::Repo(repo_command) => {
+            commands::repo::run(repo_command);
+        }
+        args::RuntipiMainCommand::Context(context_command) => {
+            commands::context::run(context_command, args.timeout);
+        }
+        args::RuntipiMainCommand::Env(env_command) => {
+            commands::env::run(env_command);
+        }
+        args::RuntipiMainCommand::Settings(settings_command) => {
+            commands::settings::run(settings_command);
+        }
+        args::RuntipiMainCommand::Status => {
+            let env_map = get_env_map();
+            let context = commands::context::active_context(args.context.as_deref());
+
+            commands::status::run(context, env_map, args.timeout);
         }
         args::RuntipiMainCommand::Debug => {
             let env_map = get_env_map();
 
-            commands::debug::run(env_map);
+            commands::debug::run(env_map, args.timeout);
         }
         args::RuntipiMainCommand::Version => {
             let env_map = get_env_map();
 
-            commands::version::run(env_map);
+            let context = commands::context::active_context(args.context.as_deref());
+
+            commands::version::run(context, env_map, args.timeout);
         }
     }
 }
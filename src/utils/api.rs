@@ -1,16 +1,35 @@
-use crate::utils::env::get_env_value;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::utils::constants::{DEFAULT_DOMAIN, DEFAULT_NGINX_PORT, DEFAULT_NGINX_PORT_SSL};
+use crate::utils::context::Context;
+use crate::utils::env::EnvMap;
+use crate::utils::schemas::{InstalledAppsResponse, RestoreAppBody, SettingsSchema, SystemLoad, UninstallAppBody, UpdateAppBody, VersionResponse};
+use crate::utils::transport::{ApiRequest, RawResponse, Transport, TransportError};
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 
-use reqwest::{
-    blocking::{Body, Client, Response},
-    Method,
-};
-use serde::{Deserialize, Serialize};
-use std::io::{Error, ErrorKind};
+use reqwest::{blocking::Client, Certificate, Method, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io::Error;
+
+/// Tokens are signed for each request so they only need to outlive a slow response
+pub const TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+pub const TOKEN_AUDIENCE: &str = "runtipi";
 
 #[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    sub: String,
+pub struct Claims {
+    pub sub: String,
+    pub aud: String,
+    pub iat: u64,
+    pub exp: u64,
+    /// Unique per token so a captured token can be told apart from the next one
+    pub jti: String,
+    /// The operation the token was signed for, eg: `app:start`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 pub fn create_client() -> Result<Client, Error> {
@@ -18,45 +37,491 @@ pub fn create_client() -> Result<Client, Error> {
 
     match client {
         Ok(c) => Ok(c),
-        Err(err) => Err(Error::new(ErrorKind::Other, format!("Error creating client: {:?}", err))),
+        Err(err) => Err(Error::other(format!("Error creating client: {:?}", err))),
+    }
+}
+
+pub fn create_token(jwt_secret: &str, scope: Option<&str>) -> Result<String, ApiError> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    let claims = Claims {
+        sub: "cli".to_string(),
+        aud: TOKEN_AUDIENCE.to_string(),
+        iat: now.as_secs(),
+        exp: (now + TOKEN_LIFETIME).as_secs(),
+        jti: hex::encode(rand::random::<[u8; 16]>()),
+        scope: scope.map(|scope| scope.to_string()),
+    };
+
+    let encoding_key = EncodingKey::from_secret(jwt_secret.as_ref());
+
+    encode(&Header::new(Algorithm::HS256), &claims, &encoding_key).map_err(|err| ApiError::Token(format!("Error creating token: {:?}", err)))
+}
+
+/// How often and how fast requests are retried while the dashboard is unreachable or restarting
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// The delay before the retry number `attempt` (starting at 0), doubled after every attempt
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub connect_timeout: Duration,
+    /// Time allowed for the whole request, reading the response included
+    pub timeout: Duration,
+    pub retry: RetryPolicy,
+}
+
+impl ClientOptions {
+    /**
+     * Read `RUNTIPI_API_CONNECT_TIMEOUT`, `RUNTIPI_API_TIMEOUT` (seconds) and `RUNTIPI_API_RETRIES` from the .env file,
+     * `timeout` comes from the `--timeout` flag and takes precedence.
+     */
+    pub fn from_env_map(env_map: &EnvMap, timeout: Option<u64>) -> ClientOptions {
+        let defaults = ClientOptions::default();
+        let read = |key: &str| env_map.get(key).and_then(|value| value.trim().parse::<u64>().ok());
+
+        ClientOptions {
+            connect_timeout: read("RUNTIPI_API_CONNECT_TIMEOUT")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.connect_timeout),
+            timeout: timeout
+                .or(read("RUNTIPI_API_TIMEOUT"))
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+            retry: RetryPolicy {
+                max_retries: read("RUNTIPI_API_RETRIES")
+                    .map(|retries| retries as u32)
+                    .unwrap_or(defaults.retry.max_retries),
+                ..defaults.retry
+            },
+        }
+    }
+}
+
+impl Default for ClientOptions {
+    fn default() -> ClientOptions {
+        ClientOptions {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(30),
+            retry: RetryPolicy {
+                max_retries: 4,
+                initial_backoff: Duration::from_millis(500),
+                max_backoff: Duration::from_secs(8),
+            },
+        }
     }
 }
 
-fn create_token() -> Result<String, Error> {
-    let claims = Claims { sub: "cli".to_string() };
-    let jwt_secret = get_env_value("JWT_SECRET");
+#[derive(Debug)]
+pub enum ApiError {
+    /// The CLI could not sign a token for the request
+    Token(String),
+    /// The client could not be set up, eg: an unreadable CA bundle
+    Config(String),
+    /// No transport could reach the dashboard, eg: it is down
+    Unreachable(String),
+    /// The request was sent but no response came back, eg: a read timeout
+    Transport(String),
+    /// 401 or 403, the dashboard did not accept the token
+    Auth { status: StatusCode, message: Option<String> },
+    /// Any other 4xx
+    Client { status: StatusCode, message: Option<String> },
+    /// 5xx
+    Server { status: StatusCode, message: Option<String> },
+    /// The response did not have the expected shape
+    Decode(String),
+}
+
+impl ApiError {
+    /// Errors worth another try: nothing reached the dashboard, or it is restarting behind the proxy
+    fn is_retryable(&self) -> bool {
+        match self {
+            ApiError::Unreachable(_) => true,
+            ApiError::Server { status, .. } => *status == StatusCode::BAD_GATEWAY || *status == StatusCode::SERVICE_UNAVAILABLE,
+            _ => false,
+        }
+    }
+
+    fn from_response(response: RawResponse) -> ApiError {
+        let status = response.status;
+        let message = server_message(&response.body);
 
-    match jwt_secret {
-        Some(secret) => {
-            let encoding_key = EncodingKey::from_secret(secret.as_ref());
-            let encoded = encode(&Header::new(Algorithm::HS256), &claims, &encoding_key);
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ApiError::Auth { status, message },
+            _ if status.is_server_error() => ApiError::Server { status, message },
+            _ => ApiError::Client { status, message },
+        }
+    }
+}
 
-            match encoded {
-                Ok(t) => Ok(t),
-                Err(err) => Err(Error::new(ErrorKind::Other, format!("Error creating token: {:?}", err))),
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Token(message) | ApiError::Config(message) => write!(f, "{}", message),
+            ApiError::Unreachable(message) => write!(f, "Unable to reach the dashboard: {}", message),
+            ApiError::Transport(message) => write!(f, "Request to the dashboard failed: {}", message),
+            ApiError::Auth { status, message: None } => {
+                write!(
+                    f,
+                    "Error code: {} - The dashboard rejected the CLI token, check JWT_SECRET in .env",
+                    status
+                )
+            }
+            ApiError::Auth {
+                status,
+                message: Some(message),
             }
+            | ApiError::Client {
+                status,
+                message: Some(message),
+            }
+            | ApiError::Server {
+                status,
+                message: Some(message),
+            } => write!(f, "Error code: {} - {}", status, message),
+            ApiError::Client { status, message: None } | ApiError::Server { status, message: None } => {
+                write!(f, "Error code: {}", status)
+            }
+            ApiError::Decode(message) => write!(f, "Unexpected response from the dashboard: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Extract the error message from a response body, the dashboard sends `{ "message": "..." }` or a list of messages
+pub fn server_message(body: &str) -> Option<String> {
+    let json = match serde_json::from_str::<Value>(body) {
+        Ok(json) => json,
+        // Plain text errors are shown as is, HTML error pages from the reverse proxy are not useful
+        Err(_) => {
+            let body = body.trim();
+            return (!body.is_empty() && !body.starts_with('<') && body.len() <= 200).then(|| body.to_string());
+        }
+    };
+
+    match json.get("message").or(json.get("error")) {
+        Some(Value::String(message)) => Some(message.clone()),
+        Some(Value::Array(messages)) => Some(messages.iter().filter_map(|message| message.as_str()).collect::<Vec<&str>>().join(", ")),
+        _ => None,
+    }
+}
+
+/// The app lifecycle endpoints `app-lifecycle/<id>/<action>` and their bodies
+#[derive(Debug)]
+pub enum AppLifecycleRequest {
+    Install(Map<String, Value>),
+    Start,
+    Stop,
+    Uninstall(UninstallAppBody),
+    Reset,
+    Update(UpdateAppBody),
+    Backup,
+    Restore(RestoreAppBody),
+}
+
+impl AppLifecycleRequest {
+    pub fn path(&self) -> &'static str {
+        match self {
+            AppLifecycleRequest::Install(_) => "install",
+            AppLifecycleRequest::Start => "start",
+            AppLifecycleRequest::Stop => "stop",
+            AppLifecycleRequest::Uninstall(_) => "uninstall",
+            AppLifecycleRequest::Reset => "reset",
+            AppLifecycleRequest::Update(_) => "update",
+            AppLifecycleRequest::Backup => "backup",
+            AppLifecycleRequest::Restore(_) => "restore",
+        }
+    }
+
+    fn method(&self) -> Method {
+        match self {
+            AppLifecycleRequest::Uninstall(_) => Method::DELETE,
+            AppLifecycleRequest::Update(_) => Method::PATCH,
+            _ => Method::POST,
         }
-        None => Err(Error::new(ErrorKind::Other, "JWT_SECRET not found in environment variables")),
+    }
+
+    fn body(&self) -> Value {
+        let body = match self {
+            AppLifecycleRequest::Install(form) => Ok(Value::Object(form.clone())),
+            AppLifecycleRequest::Uninstall(body) => serde_json::to_value(body),
+            AppLifecycleRequest::Update(body) => serde_json::to_value(body),
+            AppLifecycleRequest::Restore(body) => serde_json::to_value(body),
+            _ => Ok(Value::Object(Map::new())),
+        };
+
+        body.expect("Request bodies are always serializable")
+    }
+}
+
+/// How requests prove they come from an admin of the instance
+#[derive(Debug, Clone)]
+pub enum ClientAuth {
+    /// Sign a short-lived token for every request
+    JwtSecret(String),
+    /// A token handed over by the instance, sent as is
+    Token(String),
+    None,
+}
+
+pub struct RuntipiClient {
+    /// Tried in order until one of them reaches the dashboard
+    transports: Vec<Transport>,
+    auth: ClientAuth,
+    /// Set for the instances of a context, their files are not on this machine
+    remote: bool,
+    options: ClientOptions,
+    /// The transport that answered last, so the next requests don't go through the unreachable ones again
+    active: AtomicUsize,
+}
+
+/// Add `/api` to urls given without a path, eg: `https://tipi.example.com`
+fn normalize_api_url(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+
+    match Url::parse(url) {
+        Ok(parsed) if parsed.path() == "/" || parsed.path().is_empty() => format!("{}/api", url),
+        _ => url.to_string(),
     }
 }
 
-pub fn api_request(url: String, method: Method, raw_body: &str) -> Result<Response, Error> {
-    let client = create_client()?;
-    let token = create_token()?;
-    let auth_token = format!("Bearer {}", token);
+fn https_url(host: &str, ssl_port: &str) -> String {
+    match ssl_port {
+        "443" => format!("https://{}/api", host),
+        _ => format!("https://{}:{}/api", host, ssl_port),
+    }
+}
+
+/**
+* The dashboard urls to try, in order. `RUNTIPI_API_URL` replaces the defaults, otherwise the internal address is
+* tried first, then `DOMAIN` and `LOCAL_DOMAIN` over https for instances where the plain http entrypoint is disabled.
+*/
+pub fn api_base_urls(env_map: &EnvMap) -> Vec<String> {
+    if let Some(api_url) = env_map.get("RUNTIPI_API_URL").filter(|url| !url.is_empty()) {
+        return vec![normalize_api_url(api_url)];
+    }
 
-    let body = Body::from(raw_body.to_string());
+    let ssl_port = env_map.get("NGINX_PORT_SSL").map(|s| s.as_str()).unwrap_or(DEFAULT_NGINX_PORT_SSL);
+    let mut urls = vec![format!(
+        "http://{}:{}/api",
+        env_map.get("INTERNAL_IP").map(|s| s.as_str()).unwrap_or("localhost"),
+        env_map.get("NGINX_PORT").map(|s| s.as_str()).unwrap_or(DEFAULT_NGINX_PORT),
+    )];
 
-    let response = client
-        .request(method, url)
-        .header("Authorization", auth_token)
-        .header("Content-Type", "application/json")
-        .header("Accept", "application/json")
-        .body(body)
-        .send();
+    if let Some(domain) = env_map.get("DOMAIN").filter(|domain| !domain.is_empty() && *domain != DEFAULT_DOMAIN) {
+        urls.push(https_url(domain, ssl_port));
+    }
+    if let Some(local_domain) = env_map.get("LOCAL_DOMAIN").filter(|domain| !domain.is_empty()) {
+        urls.push(https_url(local_domain, ssl_port));
+    }
+
+    urls
+}
+
+/**
+* Build the http client for an endpoint. A custom CA bundle from `RUNTIPI_API_CA_CERT` is trusted for every endpoint,
+* and the self-signed certificate traefik serves for `LOCAL_DOMAIN` is accepted for that host only.
+*/
+fn create_endpoint_client(url: &str, env_map: &EnvMap, ca_certificates: &[Certificate], options: &ClientOptions) -> Result<Client, ApiError> {
+    let mut builder = Client::builder()
+        .user_agent("reqwest")
+        .connect_timeout(options.connect_timeout)
+        .timeout(options.timeout);
+
+    for certificate in ca_certificates {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+
+    let host = Url::parse(url).ok().and_then(|url| url.host_str().map(|host| host.to_string()));
+
+    if host.is_some() && host.as_ref() == env_map.get("LOCAL_DOMAIN") {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder.build().map_err(|err| ApiError::Config(format!("Error creating client: {}", err)))
+}
+
+fn read_ca_certificates(path: &str) -> Result<Vec<Certificate>, ApiError> {
+    let pem = std::fs::read(path).map_err(|err| ApiError::Config(format!("Unable to read the CA bundle {}: {}", path, err)))?;
+
+    Certificate::from_pem_bundle(&pem).map_err(|err| ApiError::Config(format!("Invalid CA bundle {}: {}", path, err)))
+}
+
+impl RuntipiClient {
+    pub fn new(transports: Vec<Transport>, auth: ClientAuth, options: ClientOptions) -> RuntipiClient {
+        RuntipiClient {
+            transports,
+            auth,
+            remote: false,
+            options,
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    /**
+     * A client for the dashboard reachable through the urls of `api_base_urls`. When the reverse proxy can't be
+     * reached, requests go straight to the dashboard container, by its address or with `docker exec`.
+     */
+    pub fn from_env_map(env_map: &EnvMap, options: ClientOptions) -> Result<RuntipiClient, ApiError> {
+        let ca_certificates = match env_map.get("RUNTIPI_API_CA_CERT").filter(|path| !path.is_empty()) {
+            Some(path) => read_ca_certificates(path)?,
+            None => vec![],
+        };
+
+        let mut transports = api_base_urls(env_map)
+            .into_iter()
+            .map(|url| {
+                let client = create_endpoint_client(&url, env_map, &ca_certificates, &options)?;
+                Ok(Transport::Proxy { url, client })
+            })
+            .collect::<Result<Vec<Transport>, ApiError>>()?;
+
+        transports.push(Transport::container_ip(options.timeout).map_err(ApiError::Config)?);
+        transports.push(Transport::DockerExec { timeout: options.timeout });
+
+        let auth = match env_map.get("JWT_SECRET") {
+            Some(secret) => ClientAuth::JwtSecret(secret.clone()),
+            None => ClientAuth::None,
+        };
+
+        Ok(RuntipiClient::new(transports, auth, options))
+    }
+
+    /// A client for the remote instance of a context, only reachable through its API url
+    pub fn from_context(context: &Context, options: ClientOptions) -> Result<RuntipiClient, ApiError> {
+        let ca_certificates = match &context.ca_cert {
+            Some(path) => read_ca_certificates(path)?,
+            None => vec![],
+        };
+
+        let url = normalize_api_url(&context.api_url);
+        let client = create_endpoint_client(&url, &EnvMap::new(), &ca_certificates, &options)?;
+
+        let auth = match (&context.jwt_secret, &context.token) {
+            (Some(secret), _) => ClientAuth::JwtSecret(secret.clone()),
+            (None, Some(token)) => ClientAuth::Token(token.clone()),
+            (None, None) => ClientAuth::None,
+        };
+
+        Ok(RuntipiClient {
+            remote: true,
+            ..RuntipiClient::new(vec![Transport::Proxy { url, client }], auth, options)
+        })
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    /// The host name of a remote instance, taken from the url of its API
+    pub fn remote_host(&self) -> Option<String> {
+        match self.transports.first() {
+            Some(Transport::Proxy { url, .. }) if self.remote => Url::parse(url).ok()?.host_str().map(|host| host.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Send the request and return the body of a successful response, retrying with a backoff while it makes sense
+    fn send(&self, method: Method, path: &str, scope: &str, body: Option<&Value>) -> Result<String, ApiError> {
+        let mut attempt = 0;
+
+        loop {
+            match self.send_once(method.clone(), path, scope, body) {
+                Err(err) if err.is_retryable() && attempt < self.options.retry.max_retries => {
+                    thread::sleep(self.options.retry.backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn send_once(&self, method: Method, path: &str, scope: &str, body: Option<&Value>) -> Result<String, ApiError> {
+        let token = match &self.auth {
+            ClientAuth::JwtSecret(secret) => create_token(secret, Some(scope))?,
+            ClientAuth::Token(token) => token.clone(),
+            ClientAuth::None => return Err(ApiError::Token("JWT_SECRET not found in environment variables".to_string())),
+        };
+        let request = ApiRequest {
+            method,
+            path,
+            token: &token,
+            body,
+        };
+
+        let active = self.active.load(Ordering::Relaxed);
+        let mut unreachable = vec![];
+
+        for (index, transport) in self.transports.iter().enumerate().skip(active) {
+            let response = match transport.execute(&request) {
+                Ok(response) => response,
+                Err(TransportError::Unreachable(reason)) => {
+                    unreachable.push(reason);
+                    continue;
+                }
+                Err(TransportError::Failed(err)) => return Err(ApiError::Transport(err)),
+            };
+
+            self.active.store(index, Ordering::Relaxed);
+
+            return match response.status.is_success() {
+                true => Ok(response.body),
+                false => Err(ApiError::from_response(response)),
+            };
+        }
+
+        // Start over from the first transport on the next attempt, the proxy may be back by then
+        self.active.store(0, Ordering::Relaxed);
+
+        Err(ApiError::Unreachable(format!("tried {}", unreachable.join(", "))))
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str, scope: &str) -> Result<T, ApiError> {
+        let body = self.send(Method::GET, path, scope, None)?;
+
+        serde_json::from_str::<T>(&body).map_err(|err| ApiError::Decode(err.to_string()))
+    }
+
+    pub fn app_lifecycle(&self, id: &str, request: &AppLifecycleRequest) -> Result<(), ApiError> {
+        let path = format!("app-lifecycle/{}/{}", id, request.path());
+        let scope = format!("app:{}", request.path());
+        self.send(request.method(), &path, &scope, Some(&request.body())).map(|_| ())
+    }
+
+    pub fn installed_apps(&self) -> Result<InstalledAppsResponse, ApiError> {
+        self.get("apps/installed", "app:read")
+    }
+
+    /// The raw details of an installed app, the shape changed between dashboard versions
+    pub fn app(&self, id: &str) -> Result<Value, ApiError> {
+        self.get(&format!("apps/{}", id), "app:read")
+    }
+
+    pub fn version(&self) -> Result<VersionResponse, ApiError> {
+        self.get("system/version", "system:read")
+    }
+
+    pub fn health(&self) -> Result<(), ApiError> {
+        self.send(Method::GET, "health", "system:read", None).map(|_| ())
+    }
+
+    pub fn system_load(&self) -> Result<SystemLoad, ApiError> {
+        self.get("system/load", "system:read")
+    }
 
-    match response {
-        Ok(r) => Ok(r),
-        Err(err) => Err(Error::new(ErrorKind::Other, format!("Error sending request: {:?}", err))),
+    /// The settings as seen by the dashboard, same keys as `state/settings.json`
+    pub fn settings(&self) -> Result<SettingsSchema, ApiError> {
+        self.get("system/settings", "system:read")
     }
 }
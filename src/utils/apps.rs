@@ -0,0 +1,258 @@
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::utils::env::EnvMap;
+use crate::utils::schemas::AppConfigSchema;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppUpdate {
+    pub current_version: String,
+    pub current_tipi_version: i64,
+    pub latest_version: String,
+    pub latest_tipi_version: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledApp {
+    /// The id used by the dashboard API, eg: `nextcloud` or `nextcloud:migrated` for store scoped apps
+    pub id: String,
+    /// The name of the app folder, eg: `nextcloud`
+    pub name: String,
+    /// The app store the app was installed from when using the `apps/<store>/<app>` layout
+    pub store: Option<String>,
+    pub path: PathBuf,
+}
+
+impl InstalledApp {
+    /// The docker compose project the app containers are part of
+    pub fn compose_project(&self) -> String {
+        match &self.store {
+            Some(store) => format!("{}_{}", self.name, store),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The `config.json` copied in the app folder at install time
+    pub fn config(&self) -> Option<AppConfigSchema> {
+        read_app_config(&self.path.join("config.json"))
+    }
+
+    /**
+     * The `config.json` of the app in its app store repository. An app installed from a store is only looked up in
+     * that store, another store may have an unrelated app with the same name.
+     */
+    pub fn repo_config(&self, root_folder: &Path, env_map: &EnvMap) -> Option<AppConfigSchema> {
+        match &self.store {
+            Some(store) => read_app_config(&root_folder.join("repos").join(store).join("apps").join(&self.name).join("config.json")),
+            None => find_repo_app_config(root_folder, &self.name, env_map.get("APPS_REPO_ID").map(|s| s.as_str())),
+        }
+    }
+
+    /**
+     * Compare the installed `config.json` with the one in the app store, `None` when the app is up to date or when
+     * either config can't be read since the installed version is unknown
+     */
+    pub fn available_update(&self, root_folder: &Path, env_map: &EnvMap) -> Option<AppUpdate> {
+        self.update_from(self.config()?, root_folder, env_map)
+    }
+
+    /// Compare `current`, the installed `config.json` already read by the caller, with the one in the app store
+    pub fn update_from(&self, current: AppConfigSchema, root_folder: &Path, env_map: &EnvMap) -> Option<AppUpdate> {
+        let latest = self.repo_config(root_folder, env_map)?;
+
+        if latest.tipi_version <= current.tipi_version {
+            return None;
+        }
+
+        Some(AppUpdate {
+            current_version: current.version,
+            current_tipi_version: current.tipi_version,
+            latest_version: latest.version,
+            latest_tipi_version: latest.tipi_version,
+        })
+    }
+
+    /// The folder holding the backup archives of the app, eg: `backups/nextcloud`
+    pub fn backups_path(&self, root_folder: &Path) -> PathBuf {
+        match &self.store {
+            Some(store) => root_folder.join("backups").join(store).join(&self.name),
+            None => root_folder.join("backups").join(&self.name),
+        }
+    }
+
+    /// The folder holding the user overrides of the app, eg: `user-config/nextcloud`
+    pub fn user_config_path(&self, root_folder: &Path) -> PathBuf {
+        match &self.store {
+            Some(store) => root_folder.join("user-config").join(store).join(&self.name),
+            None => root_folder.join("user-config").join(&self.name),
+        }
+    }
+
+    /// The folder holding the app data, eg: `app-data/nextcloud`
+    pub fn data_path(&self, env_map: &EnvMap) -> PathBuf {
+        let root_folder = env_map
+            .get("RUNTIPI_APP_DATA_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+
+        match &self.store {
+            Some(store) => root_folder.join("app-data").join(store).join(&self.name),
+            None => root_folder.join("app-data").join(&self.name),
+        }
+    }
+}
+
+pub fn read_app_config(path: &Path) -> Option<AppConfigSchema> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str::<AppConfigSchema>(&content).ok()
+}
+
+/**
+* Look for the `config.json` of an app in the cloned repositories. The preferred repository is searched first,
+* then every other repository in alphabetical order.
+*/
+pub fn find_repo_app_config(root_folder: &Path, app_name: &str, preferred_repo: Option<&str>) -> Option<AppConfigSchema> {
+    let repos_folder = root_folder.join("repos");
+
+    if let Some(repo) = preferred_repo {
+        if let Some(config) = read_app_config(&repos_folder.join(repo).join("apps").join(app_name).join("config.json")) {
+            return Some(config);
+        }
+    }
+
+    sorted_sub_folders(&repos_folder)
+        .unwrap_or_default()
+        .iter()
+        .find_map(|repo| read_app_config(&repo.join("apps").join(app_name).join("config.json")))
+}
+
+fn is_app_folder(path: &Path) -> bool {
+    path.join("docker-compose.yml").exists() || path.join("docker-compose.json").exists()
+}
+
+pub fn sorted_sub_folders(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut folders = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect::<Vec<PathBuf>>();
+
+    folders.sort();
+
+    Ok(folders)
+}
+
+pub fn folder_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/**
+* List the apps installed in the `apps` folder. Supports both the legacy layout `apps/<app>`
+* and the app store layout `apps/<store>/<app>`.
+*/
+pub fn get_installed_apps(root_folder: &Path) -> Result<Vec<InstalledApp>, Error> {
+    let apps_folder = root_folder.join("apps");
+
+    if !apps_folder.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut apps = vec![];
+
+    for folder in sorted_sub_folders(&apps_folder)? {
+        let folder_name = folder_name(&folder);
+
+        if is_app_folder(&folder) {
+            apps.push(InstalledApp {
+                id: folder_name.clone(),
+                name: folder_name,
+                store: None,
+                path: folder,
+            });
+            continue;
+        }
+
+        for app_folder in sorted_sub_folders(&folder)? {
+            if is_app_folder(&app_folder) {
+                let app_name = self::folder_name(&app_folder);
+
+                apps.push(InstalledApp {
+                    id: format!("{}:{}", app_name, folder_name),
+                    name: app_name,
+                    store: Some(folder_name.clone()),
+                    path: app_folder,
+                });
+            }
+        }
+    }
+
+    Ok(apps)
+}
+
+/// Find an installed app by its full id (`nextcloud:migrated`) or by its name alone (`nextcloud`)
+pub fn find_installed_app(root_folder: &Path, id: &str) -> Result<InstalledApp, Error> {
+    let apps = get_installed_apps(root_folder)?;
+
+    if let Some(app) = apps.iter().find(|app| app.id == id) {
+        return Ok(app.clone());
+    }
+
+    let mut matches = apps.into_iter().filter(|app| app.name == id).collect::<Vec<InstalledApp>>();
+
+    match matches.len() {
+        0 => Err(Error::new(std::io::ErrorKind::NotFound, format!("App {} is not installed", id))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Error::other(format!(
+            "App {} is installed from several app stores, use one of: {}",
+            id,
+            matches.iter().map(|app| app.id.as_str()).collect::<Vec<&str>>().join(", ")
+        ))),
+    }
+}
+
+/// Convert a glob pattern where `*` matches any characters and `?` a single one to an anchored regex
+pub fn glob_to_regex(glob: &str) -> Result<Regex, regex::Error> {
+    let mut pattern = String::from("^");
+
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern)
+}
+
+/// The ids of the installed apps whose id or name matches `pattern`, every app when there is no pattern
+pub fn select_app_ids(apps: &[InstalledApp], pattern: Option<&Regex>) -> Vec<String> {
+    apps.iter()
+        .filter(|app| pattern.is_none_or(|pattern| pattern.is_match(&app.id) || pattern.is_match(&app.name)))
+        .map(|app| app.id.clone())
+        .collect()
+}
+
+/**
+* Whether a log line is about the app, the app name has to appear as a whole id so `nextcloud` doesn't match
+* `nextcloud-aio`. An app from a store is matched by its full id or its name alone, but not by the id of the
+* same app in another store.
+*/
+pub fn mentions_app(line: &str, app_id: &str) -> bool {
+    let (name, store) = match app_id.split_once(':') {
+        Some((name, store)) => (name, Some(store)),
+        None => (app_id, None),
+    };
+    let store = store.map(|store| format!("(?::{})?", regex::escape(store))).unwrap_or_default();
+    let pattern = format!(
+        r"(?:^|[^A-Za-z0-9_-]){}{}(?:$|[^A-Za-z0-9_:-]|:(?:$|[^A-Za-z0-9_-]))",
+        regex::escape(name),
+        store
+    );
+
+    Regex::new(&pattern).is_ok_and(|regex| regex.is_match(line))
+}
@@ -0,0 +1,51 @@
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub struct BackupArchive {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// Archives of the backups folder, newest first
+pub fn list_archives(backups_path: &Path) -> Vec<BackupArchive> {
+    let mut archives = fs::read_dir(backups_path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let metadata = entry.metadata().ok()?;
+                    let name = entry.file_name().to_string_lossy().to_string();
+
+                    if !metadata.is_file() || !name.ends_with(".tar.gz") {
+                        return None;
+                    }
+
+                    Some(BackupArchive {
+                        name,
+                        path: entry.path(),
+                        size: metadata.len(),
+                        modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    })
+                })
+                .collect::<Vec<BackupArchive>>()
+        })
+        .unwrap_or_default();
+
+    archives.sort_by_key(|archive| std::cmp::Reverse(archive.modified));
+
+    archives
+}
+
+/// Delete the oldest archives so only `keep` of them remain
+pub fn prune_archives(backups_path: &Path, keep: NonZeroUsize) -> Vec<String> {
+    list_archives(backups_path)
+        .into_iter()
+        .skip(keep.get())
+        .filter(|archive| fs::remove_file(&archive.path).is_ok())
+        .map(|archive| archive.name)
+        .collect()
+}
@@ -6,3 +6,6 @@ pub const DEFAULT_LOCAL_DOMAIN: &str = "tipi.local";
 pub const DOCKER_COMPOSE_YML: &str = include_str!("../assets/docker-compose.yml");
 pub const VERSION: &str = include_str!("../assets/VERSION");
 pub const DEFAULT_FORWARD_AUTH_URL: &str = "http://runtipi:3000/api/auth/traefik";
+pub const DASHBOARD_CONTAINER: &str = "runtipi";
+pub const DASHBOARD_PORT: &str = "3000";
+pub const MAIN_NETWORK: &str = "runtipi_tipi_main_network";
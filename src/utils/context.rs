@@ -0,0 +1,108 @@
+use std::env;
+use std::fs;
+use std::io::{Error, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The name of the instance in the current directory, it can't be used for a stored context
+pub const DEFAULT_CONTEXT: &str = "default";
+
+/// A remote runtipi instance reached through its API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Context {
+    pub name: String,
+
+    /// eg: https://tipi.example.com/api
+    pub api_url: String,
+
+    /// Used to sign a short-lived token for every request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt_secret: Option<String>,
+
+    /// Sent as is when the JWT secret of the instance is not shared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// A CA bundle to trust in addition to the system certificates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContextStore {
+    /// `None` when the instance in the current directory is used
+    #[serde(default)]
+    pub current: Option<String>,
+
+    #[serde(default)]
+    pub contexts: Vec<Context>,
+}
+
+/// `$XDG_CONFIG_HOME/runtipi-cli/contexts.json`, falling back to `~/.config`
+pub fn contexts_path() -> PathBuf {
+    let config_dir = env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap_or_default()).join(".config"));
+
+    config_dir.join("runtipi-cli").join("contexts.json")
+}
+
+impl ContextStore {
+    pub fn load(path: &Path) -> Result<ContextStore, Error> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(ContextStore::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /**
+     * The file holds secrets so it is only readable by the current user. It is written to a temporary file created
+     * with that mode and renamed over the previous one, the secrets are never readable by others, even for a moment.
+     */
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp_path = path.with_extension("json.tmp");
+        let _ = fs::remove_file(&temp_path);
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let written = options
+            .open(&temp_path)
+            .and_then(|mut file| file.write_all(serde_json::to_string_pretty(self)?.as_bytes()))
+            .and_then(|_| fs::rename(&temp_path, path));
+
+        if written.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        written
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Context> {
+        self.contexts.iter().find(|context| context.name == name)
+    }
+
+    /// The context to use, `name` comes from the `--context` flag and takes precedence over the current one
+    pub fn resolve(&self, name: Option<&str>) -> Result<Option<&Context>, String> {
+        match name.or(self.current.as_deref()) {
+            None | Some(DEFAULT_CONTEXT) => Ok(None),
+            Some(name) => self
+                .get(name)
+                .map(Some)
+                .ok_or(format!("Context {} not found, see `runtipi-cli context list`", name)),
+        }
+    }
+}
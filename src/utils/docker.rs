@@ -0,0 +1,102 @@
+use std::io::Error;
+use std::process::Command;
+
+const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+
+#[derive(Debug, Clone)]
+pub struct ContainerInfo {
+    pub name: String,
+    pub project: String,
+    pub service: String,
+    /// The container state, eg: `running`, `exited` or `restarting`
+    pub state: String,
+    /// The human readable status, eg: `Up 2 hours (healthy)`
+    pub status: String,
+    /// The published ports, eg: `0.0.0.0:8080->80/tcp`
+    pub ports: String,
+}
+
+impl ContainerInfo {
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+
+    /// Running and either healthy or without a healthcheck
+    pub fn is_ready(&self) -> bool {
+        self.is_running() && !self.status.contains("(health: starting)") && !self.status.contains("(unhealthy)")
+    }
+}
+
+/**
+* List every container (running or not) that belongs to a docker compose project.
+* When `project` is set, only the containers of that project are returned.
+*/
+pub fn get_compose_containers(project: Option<&str>) -> Result<Vec<ContainerInfo>, Error> {
+    let filter = match project {
+        Some(project) => format!("label={}={}", COMPOSE_PROJECT_LABEL, project),
+        None => format!("label={}", COMPOSE_PROJECT_LABEL),
+    };
+    let format = format!(
+        "{{{{.Names}}}}\t{{{{.Label \"{}\"}}}}\t{{{{.Label \"{}\"}}}}\t{{{{.State}}}}\t{{{{.Status}}}}\t{{{{.Ports}}}}",
+        COMPOSE_PROJECT_LABEL, COMPOSE_SERVICE_LABEL
+    );
+
+    let output = Command::new("docker")
+        .arg("ps")
+        .arg("-a")
+        .arg("--filter")
+        .arg(filter)
+        .arg("--format")
+        .arg(format)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "Failed to list containers: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let containers = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts = line.split('\t').collect::<Vec<&str>>();
+
+            match parts.as_slice() {
+                [name, project, service, state, status, ports] => Some(ContainerInfo {
+                    name: name.to_string(),
+                    project: project.to_string(),
+                    service: service.to_string(),
+                    state: state.to_string(),
+                    status: status.to_string(),
+                    ports: ports.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Ok(containers)
+}
+
+/// The ip address of a container on a docker network
+pub fn get_container_ip(container: &str, network: &str) -> Result<String, Error> {
+    let output = Command::new("docker")
+        .arg("inspect")
+        .arg("--format")
+        .arg(format!(
+            "{{{{with index .NetworkSettings.Networks \"{}\"}}}}{{{{.IPAddress}}}}{{{{end}}}}",
+            network
+        ))
+        .arg(container)
+        .output()?;
+
+    let ip = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if !output.status.success() || ip.is_empty() {
+        return Err(Error::other(format!("Container {} has no address on network {}", container, network)));
+    }
+
+    Ok(ip)
+}
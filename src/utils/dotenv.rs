@@ -0,0 +1,456 @@
+/*!
+* A reader and writer for `.env` files following the rules docker compose uses:
+*
+* - blank lines and lines starting with `#` are ignored, keys may be prefixed with `export`
+* - unquoted values end at the end of the line or at a ` #` comment, surrounding whitespace is trimmed
+* - single-quoted values are taken literally and may span several lines
+* - double-quoted values may span several lines and support `\n`, `\r`, `\t`, `\"`, `\\` and `\$`
+* - `$VAR`, `${VAR}`, `${VAR:-default}`, `${VAR-default}`, `${VAR:+alt}` and `${VAR+alt}` are
+*   interpolated in unquoted and double-quoted values from the variables defined earlier in the file,
+*   `$$` is a literal `$`
+*/
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotenvError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotenvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+pub fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_blank(c: char) -> bool {
+    c == ' ' || c == '\t' || c == '\r'
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    fn skip_blanks(&mut self) {
+        while self.peek().is_some_and(is_blank) {
+            self.pos += 1;
+        }
+    }
+
+    /// Move past the next newline
+    fn skip_line(&mut self) {
+        while let Some(c) = self.bump() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn error(&self, pos: usize, message: String) -> DotenvError {
+        DotenvError {
+            line: self.chars[..pos].iter().filter(|c| **c == '\n').count() + 1,
+            message,
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !is_blank(c) && c != '=' && c != '\n' && c != '#') {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn entry(&mut self, entries: &[(String, String)]) -> Result<(String, String), DotenvError> {
+        let mut key_start = self.pos;
+        let mut key = self.word();
+
+        if key == "export" && self.peek().is_some_and(is_blank) {
+            self.skip_blanks();
+            key_start = self.pos;
+            key = self.word();
+        }
+
+        self.skip_blanks();
+        if key.is_empty() || self.peek() != Some('=') {
+            return Err(self.error(key_start, "expected KEY=VALUE".to_string()));
+        }
+        if !is_valid_key(&key) {
+            return Err(self.error(key_start, format!("'{}' is not a valid variable name", key)));
+        }
+        self.pos += 1;
+        self.skip_blanks();
+
+        let value = match self.peek() {
+            Some('\'') => self.single_quoted()?,
+            Some('"') => self.double_quoted(entries)?,
+            _ => return Ok((key, self.unquoted(entries)?)),
+        };
+
+        self.skip_blanks();
+        match self.peek() {
+            None | Some('\n') => self.skip_line(),
+            Some('#') => self.skip_line(),
+            Some(_) => return Err(self.error(self.pos, "unexpected characters after the closing quote".to_string())),
+        }
+
+        Ok((key, value))
+    }
+
+    fn single_quoted(&mut self) -> Result<String, DotenvError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('\'') => return Ok(value),
+                Some(c) => value.push(c),
+                None => return Err(self.error(start, "unterminated single-quoted value".to_string())),
+            }
+        }
+    }
+
+    fn double_quoted(&mut self, entries: &[(String, String)]) -> Result<String, DotenvError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('"' | '\\' | '$')) => value.push(c),
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => return Err(self.error(start, "unterminated double-quoted value".to_string())),
+                },
+                Some('$') => self.interpolate(entries, &mut value)?,
+                Some(c) => value.push(c),
+                None => return Err(self.error(start, "unterminated double-quoted value".to_string())),
+            }
+        }
+    }
+
+    fn unquoted(&mut self, entries: &[(String, String)]) -> Result<String, DotenvError> {
+        let mut value = String::new();
+
+        loop {
+            match self.peek() {
+                None => break,
+                Some('\n') => {
+                    self.pos += 1;
+                    break;
+                }
+                // `#` only starts a comment after whitespace, `KEY=a#b` is `a#b`
+                Some('#') if is_blank(self.chars[self.pos - 1]) => {
+                    self.skip_line();
+                    break;
+                }
+                Some('$') => {
+                    self.pos += 1;
+                    self.interpolate(entries, &mut value)?;
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    value.push(c);
+                }
+            }
+        }
+
+        Ok(value.trim_end_matches(is_blank).to_string())
+    }
+
+    /// Expand the variable following a `$` into `value`
+    fn interpolate(&mut self, entries: &[(String, String)], value: &mut String) -> Result<(), DotenvError> {
+        let lookup = |name: &str| entries.iter().rev().find(|(key, _)| key == name).map(|(_, value)| value.as_str());
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+        match self.peek() {
+            Some('$') => {
+                self.pos += 1;
+                value.push('$');
+            }
+            Some('{') => {
+                let start = self.pos - 1;
+                self.pos += 1;
+
+                let name_start = self.pos;
+                while self.peek().is_some_and(is_name_char) {
+                    self.pos += 1;
+                }
+                let name = self.chars[name_start..self.pos].iter().collect::<String>();
+
+                let modifier_start = self.pos;
+                while self.peek().is_some_and(|c| c != '}' && c != '\n') {
+                    self.pos += 1;
+                }
+                if self.bump() != Some('}') {
+                    return Err(self.error(start, format!("unterminated variable '${{{}'", name)));
+                }
+                let modifier = self.chars[modifier_start..self.pos - 1].iter().collect::<String>();
+
+                let current = lookup(&name);
+                let expanded = match modifier.as_str() {
+                    "" => current.unwrap_or_default(),
+                    _ if modifier.starts_with(":-") => current.filter(|v| !v.is_empty()).unwrap_or(&modifier[2..]),
+                    _ if modifier.starts_with('-') => current.unwrap_or(&modifier[1..]),
+                    _ if modifier.starts_with(":+") => current.filter(|v| !v.is_empty()).map(|_| &modifier[2..]).unwrap_or_default(),
+                    _ if modifier.starts_with('+') => current.map(|_| &modifier[1..]).unwrap_or_default(),
+                    _ => return Err(self.error(start, format!("unsupported variable expression '${{{}{}}}'", name, modifier))),
+                };
+                value.push_str(expanded);
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let name_start = self.pos;
+                while self.peek().is_some_and(is_name_char) {
+                    self.pos += 1;
+                }
+                let name = self.chars[name_start..self.pos].iter().collect::<String>();
+                value.push_str(lookup(&name).unwrap_or_default());
+            }
+            _ => value.push('$'),
+        }
+
+        Ok(())
+    }
+}
+
+enum Item {
+    Entry(String, String),
+    Invalid(DotenvError),
+    /// A blank line or a comment
+    Other,
+}
+
+/// Split the input in items along with their source text, an invalid entry is skipped up to the end of its line
+fn scan(input: &str) -> Vec<(Item, String)> {
+    let mut lexer = Lexer {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let mut entries = vec![];
+    let mut items = vec![];
+
+    while lexer.peek().is_some() {
+        let start = lexer.pos;
+        lexer.skip_blanks();
+
+        let item = match lexer.peek() {
+            None | Some('\n') | Some('#') => {
+                lexer.skip_line();
+                Item::Other
+            }
+            Some(_) => match lexer.entry(&entries) {
+                Ok(entry) => {
+                    entries.push(entry.clone());
+                    Item::Entry(entry.0, entry.1)
+                }
+                Err(error) => {
+                    // Resume on the next line even when an unterminated quote went past it
+                    lexer.pos = start;
+                    lexer.skip_line();
+                    Item::Invalid(error)
+                }
+            },
+        };
+
+        let raw = lexer.chars[start..lexer.pos].iter().collect::<String>();
+        items.push((item, raw.strip_suffix('\n').unwrap_or(&raw).to_string()));
+    }
+
+    items
+}
+
+/// Parse every entry, an invalid one is reported and skipped
+pub fn parse_lenient(input: &str) -> (Vec<(String, String)>, Vec<DotenvError>) {
+    let mut entries = vec![];
+    let mut errors = vec![];
+
+    for (item, _) in scan(input) {
+        match item {
+            Item::Entry(key, value) => entries.push((key, value)),
+            Item::Invalid(error) => errors.push(error),
+            Item::Other => {}
+        }
+    }
+
+    (entries, errors)
+}
+
+/// The entries of a `.env` file in order, duplicated keys included
+pub fn parse(input: &str) -> Result<Vec<(String, String)>, Vec<DotenvError>> {
+    match parse_lenient(input) {
+        (entries, errors) if errors.is_empty() => Ok(entries),
+        (_, errors) => Err(errors),
+    }
+}
+
+fn is_bare_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_-./:@,+=%^~*?!&()[]{}<>|;".contains(c)
+}
+
+/// Quote a value so that both docker compose and `parse` read it back unchanged
+pub fn quote_value(value: &str) -> String {
+    if value.chars().all(is_bare_char) {
+        return value.to_string();
+    }
+
+    // Single quotes keep `$`, `#` and spaces as is but can't hold a quote or be escaped
+    if !value.contains(['\'', '\n', '\r']) {
+        return format!("'{}'", value);
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '$' => quoted.push_str("$$"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+pub fn format_entry(key: &str, value: &str) -> String {
+    format!("{}={}", key, quote_value(value))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DocumentLine {
+    Entry {
+        key: String,
+        value: String,
+        raw: String,
+    },
+    /// Comments, blank lines and invalid entries are written back as they were read
+    Other(String),
+}
+
+/**
+* A `.env` file that can be edited without losing its layout: the order of the keys, comments and
+* unknown keys are kept and an entry is only rewritten when its value changes.
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvDocument {
+    lines: Vec<DocumentLine>,
+}
+
+impl EnvDocument {
+    pub fn parse(input: &str) -> EnvDocument {
+        let lines = scan(input)
+            .into_iter()
+            .map(|(item, raw)| match item {
+                Item::Entry(key, value) => DocumentLine::Entry { key, value, raw },
+                Item::Invalid(_) | Item::Other => DocumentLine::Other(raw),
+            })
+            .collect();
+
+        EnvDocument { lines }
+    }
+
+    /// The value of the last entry setting `key`, the one compose uses
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().rev().find_map(|line| match line {
+            DocumentLine::Entry { key: k, value, .. } if k == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Set `key` in place, or append it when missing. Duplicated entries of the key are dropped
+    pub fn set(&mut self, key: &str, value: &str) {
+        let is_key = |line: &DocumentLine| matches!(line, DocumentLine::Entry { key: k, .. } if k == key);
+
+        match self.lines.iter().position(is_key) {
+            Some(index) => {
+                if !matches!(&self.lines[index], DocumentLine::Entry { value: v, .. } if v == value) {
+                    self.lines[index] = DocumentLine::Entry {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        raw: format_entry(key, value),
+                    };
+                }
+
+                self.lines = std::mem::take(&mut self.lines)
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, line)| *i == index || !is_key(line))
+                    .map(|(_, line)| line)
+                    .collect();
+            }
+            None => self.lines.push(DocumentLine::Entry {
+                key: key.to_string(),
+                value: value.to_string(),
+                raw: format_entry(key, value),
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.lines.retain(|line| !matches!(line, DocumentLine::Entry { key: k, .. } if k == key));
+    }
+
+    /// A copy where the entries `hide` returns a placeholder for are written `KEY=<placeholder>`, multi-line values included
+    pub fn redacted(&self, hide: impl Fn(&str) -> Option<String>) -> EnvDocument {
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| match line {
+                DocumentLine::Entry { key, value, .. } => match hide(key) {
+                    Some(placeholder) => DocumentLine::Entry {
+                        key: key.clone(),
+                        value: value.clone(),
+                        raw: format!("{}={}", key, placeholder),
+                    },
+                    None => line.clone(),
+                },
+                DocumentLine::Other(_) => line.clone(),
+            })
+            .collect();
+
+        EnvDocument { lines }
+    }
+}
+
+impl fmt::Display for EnvDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                DocumentLine::Entry { raw, .. } | DocumentLine::Other(raw) => writeln!(f, "{}", raw)?,
+            }
+        }
+
+        Ok(())
+    }
+}
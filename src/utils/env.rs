@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use std::io::Error;
 
 use crate::utils::constants::{DEFAULT_NGINX_PORT, DEFAULT_NGINX_PORT_SSL};
+use crate::utils::dotenv::{self, EnvDocument};
 use crate::utils::schemas;
 use crate::utils::seed::generate_seed;
+use crate::utils::settings::{validate_settings, SettingsProblem, Severity};
 use crate::utils::system::{derive_entropy, get_architecture, get_internal_ip, get_seed};
 
 use super::constants::{DEFAULT_DOMAIN, DEFAULT_FORWARD_AUTH_URL, DEFAULT_LOCAL_DOMAIN, DEFAULT_POSTGRES_PORT};
@@ -22,45 +24,79 @@ pub fn get_env_map() -> EnvMap {
     env_string_to_map(&env_file)
 }
 
+/// Variables `debug` and `env` don't print unless asked to, they give access to the instance or tell where it is
+pub fn is_secret_key(key: &str) -> bool {
+    key == "DOMAIN" || ["PASSWORD", "SECRET", "TOKEN"].iter().any(|secret| key.contains(secret))
+}
+
 pub fn get_env_value(key: &str) -> Option<String> {
     let env_map = get_env_map();
     env_map.get(key).map(|value| value.to_string())
 }
 
 pub fn env_string_to_map(env_string: &str) -> EnvMap {
-    let mut env_map = std::collections::HashMap::new();
-
-    for line in env_string.lines() {
-        // If line is empty or starts with #, skip it
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
+    let (entries, errors) = dotenv::parse_lenient(env_string);
 
-        let mut split = line.splitn(2, '=');
-        match (split.next(), split.next()) {
-            (Some(key), Some(value)) => {
-                env_map.insert(key.to_string(), value.to_string());
-            }
-            _ => {
-                eprintln!("Warning: Line '{}' is not in the correct format", line);
-            }
-        }
+    for error in errors {
+        eprintln!("Warning: {}", error);
     }
 
-    env_map
+    entries.into_iter().collect()
 }
 
-pub fn env_map_to_string(env_map: &EnvMap) -> String {
-    let mut env_string = String::new();
+/// Check every line of an env file and report all the invalid ones
+pub fn validate_env_string(env_string: &str) -> Result<(), Vec<String>> {
+    dotenv::parse(env_string)
+        .map(|_| ())
+        .map_err(|errors| errors.iter().map(|error| error.to_string()).collect())
+}
 
-    for (key, value) in env_map {
-        env_string.push_str(&format!("{}={}\n", key, value));
-    }
+/// Set `key` in an env file, replacing its current line or appending it, comments and other lines are kept
+pub fn set_env_value(env_string: &str, key: &str, value: &str) -> String {
+    let mut document = EnvDocument::parse(env_string);
+    document.set(key, value);
+    document.to_string()
+}
+
+/// Remove every line setting `key` from an env file
+pub fn unset_env_value(env_string: &str, key: &str) -> String {
+    let mut document = EnvDocument::parse(env_string);
+    document.remove(key);
+    document.to_string()
+}
 
-    env_string
+/// The content of `.env` as the next start writes it
+pub struct RenderedEnv {
+    pub current: String,
+    pub content: String,
+    /// The values managed by the CLI, saved to tell a manual edit apart on the next start
+    pub values: BTreeMap<String, String>,
+    pub warnings: Vec<String>,
 }
 
-pub fn generate_env_file(custom_env_file_path: Option<PathBuf>) -> Result<(), Error> {
+/// Variables set by the user, merged over the generated ones on every start
+pub fn user_env_file_path(root_folder: &Path) -> PathBuf {
+    root_folder.join("user-config").join(".env")
+}
+
+fn snapshot_path(root_folder: &Path) -> PathBuf {
+    root_folder.join("state").join("generated-env.json")
+}
+
+/// The values written to `.env` by the last start
+pub fn read_generated_snapshot(root_folder: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(snapshot_path(root_folder))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/**
+* Regenerate `.env` from settings.json. Comments, key order and keys the CLI doesn't manage are kept. Values from
+* `user-config/.env` and `--env-file` override the generated ones, a manual edit of a generated key in `.env` is
+* overwritten and reported in the returned warnings.
+*/
+pub fn generate_env_file(custom_env_file_path: Option<PathBuf>) -> Result<Vec<String>, Error> {
     let root_folder: PathBuf = env::current_dir().expect("Unable to get current directory");
     let env_file_path = root_folder.join(".env");
     let state_path = root_folder.join("state");
@@ -81,33 +117,57 @@ pub fn generate_env_file(custom_env_file_path: Option<PathBuf>) -> Result<(), Er
 
     generate_seed(&root_folder)?;
 
-    let env_file = std::fs::read_to_string(&env_file_path)?;
-    let env_map = env_string_to_map(&env_file);
+    let rendered = render_env_file(&root_folder, custom_env_file_path.as_deref())?;
+
+    if rendered.content != rendered.current {
+        std::fs::write(&env_file_path, &rendered.content)?;
+    }
+
+    std::fs::write(snapshot_path(&root_folder), serde_json::to_string_pretty(&rendered.values)?)?;
+
+    Ok(rendered.warnings)
+}
+
+/// Build the next `.env` without writing anything, the instance must have been started once for its seed
+pub fn render_env_file(root_folder: &Path, custom_env_file_path: Option<&Path>) -> Result<RenderedEnv, Error> {
+    let env_file = std::fs::read_to_string(root_folder.join(".env")).unwrap_or_default();
+    let mut document = EnvDocument::parse(&env_file);
+    let mut warnings = vec![];
+
+    let json_string = std::fs::read_to_string(root_folder.join("state").join("settings.json")).unwrap_or("{}".to_string());
+    let (errors, notices): (Vec<SettingsProblem>, Vec<SettingsProblem>) = validate_settings(&json_string)
+        .into_iter()
+        .partition(|problem| problem.severity == Severity::Error);
+
+    if !errors.is_empty() {
+        let list = errors.iter().map(|problem| format!("  - {}", problem)).collect::<Vec<String>>();
+        return Err(Error::other(format!(
+            "Invalid state/settings.json, run `runtipi-cli settings validate` for details:\n{}",
+            list.join("\n")
+        )));
+    }
+    warnings.extend(notices.iter().map(|problem| format!("state/settings.json {}", problem)));
 
-    let json_string = std::fs::read_to_string(&settings_file_path)?;
     let parsed_json: schemas::SettingsSchema = serde_json::from_str(&json_string)?;
 
     let version = std::fs::read_to_string(root_folder.join("VERSION"))?;
 
-    // Create a new env map with the default values
-    let mut new_env_map: EnvMap = HashMap::new();
+    let seed = get_seed(root_folder)?;
 
-    let seed = get_seed(&root_folder)?;
-
-    let postgres_password: String = env_map
+    let postgres_password: String = document
         .get("POSTGRES_PASSWORD")
-        .unwrap_or(&derive_entropy("postgres_password", &seed))
-        .to_string();
-    let rabbitmq_password: String = env_map
+        .map(|password| password.to_string())
+        .unwrap_or(derive_entropy("postgres_password", &seed));
+    let rabbitmq_password: String = document
         .get("RABBITMQ_PASSWORD")
-        .unwrap_or(&derive_entropy("rabbitmq_password", &seed))
-        .to_string();
+        .map(|password| password.to_string())
+        .unwrap_or(derive_entropy("rabbitmq_password", &seed));
 
     let app_data_path = parsed_json.app_data_path.or(parsed_json.storage_path.clone());
 
-    if app_data_path.is_some() {
+    if let Some(app_data_path) = &app_data_path {
         // Test if the path is valid
-        let temp_app_data_path = PathBuf::from(&app_data_path.as_ref().unwrap());
+        let temp_app_data_path = PathBuf::from(app_data_path);
 
         if !temp_app_data_path.exists() {
             return Err(Error::new(
@@ -120,67 +180,136 @@ pub fn generate_env_file(custom_env_file_path: Option<PathBuf>) -> Result<(), Er
         }
     }
 
-    // Insert the default values into the new env map
-    new_env_map.insert("INTERNAL_IP".to_string(), parsed_json.internal_ip.unwrap_or(get_internal_ip()));
-    new_env_map.insert("ARCHITECTURE".to_string(), get_architecture().unwrap().to_string());
-    new_env_map.insert("TIPI_VERSION".to_string(), version);
-    new_env_map.insert("ROOT_FOLDER_HOST".to_string(), root_folder.display().to_string());
-    new_env_map.insert(
-        "NGINX_PORT".to_string(),
-        parsed_json.nginx_port.unwrap_or(StringOrInt::from(DEFAULT_NGINX_PORT)).as_string(),
-    );
-    new_env_map.insert(
-        "NGINX_PORT_SSL".to_string(),
-        parsed_json
-            .nginx_ssl_port
-            .unwrap_or(StringOrInt::from(DEFAULT_NGINX_PORT_SSL))
-            .as_string(),
-    );
-    new_env_map.insert(
-        "RUNTIPI_APP_DATA_PATH".to_string(),
-        app_data_path.unwrap_or(root_folder.display().to_string()),
-    );
-    new_env_map.insert("POSTGRES_HOST".to_string(), "runtipi-db".to_string());
-    new_env_map.insert(
-        "POSTGRES_PORT".to_string(),
-        parsed_json.postgres_port.unwrap_or(StringOrInt::from(DEFAULT_POSTGRES_PORT)).as_string(),
-    );
-    new_env_map.insert("POSTGRES_DBNAME".to_string(), "tipi".to_string());
-    new_env_map.insert("POSTGRES_USERNAME".to_string(), "tipi".to_string());
-    new_env_map.insert("POSTGRES_PASSWORD".to_string(), postgres_password);
-    new_env_map.insert("RABBITMQ_HOST".to_string(), "runtipi-queue".to_string());
-    new_env_map.insert("RABBITMQ_USERNAME".to_string(), "tipi".to_string());
-    new_env_map.insert("RABBITMQ_PASSWORD".to_string(), rabbitmq_password);
-    new_env_map.insert("DOMAIN".to_string(), parsed_json.domain.unwrap_or(DEFAULT_DOMAIN.to_string()));
-    new_env_map.insert(
-        "LOCAL_DOMAIN".to_string(),
-        parsed_json.local_domain.unwrap_or(DEFAULT_LOCAL_DOMAIN.to_string()),
-    );
-    new_env_map.insert(
-        "RUNTIPI_FORWARD_AUTH_URL".to_string(),
-        parsed_json.forward_auth_url.unwrap_or(DEFAULT_FORWARD_AUTH_URL.to_string()),
-    );
-    new_env_map.insert("LOG_LEVEL".to_string(), parsed_json.log_level.unwrap_or("info".to_string()));
+    /*
+     * The generated keys in the order they are written to a new .env. Existing keys keep their place and are only
+     * rewritten when their value changes, optional keys are removed when they are not set in settings.json.
+     */
+    let generated: Vec<(&str, Option<String>)> = vec![
+        ("INTERNAL_IP", Some(parsed_json.internal_ip.unwrap_or(get_internal_ip()))),
+        ("ARCHITECTURE", Some(get_architecture().unwrap().to_string())),
+        ("TIPI_VERSION", Some(version)),
+        ("ROOT_FOLDER_HOST", Some(root_folder.display().to_string())),
+        (
+            "NGINX_PORT",
+            Some(parsed_json.nginx_port.unwrap_or(StringOrInt::from(DEFAULT_NGINX_PORT)).as_string()),
+        ),
+        (
+            "NGINX_PORT_SSL",
+            Some(
+                parsed_json
+                    .nginx_ssl_port
+                    .unwrap_or(StringOrInt::from(DEFAULT_NGINX_PORT_SSL))
+                    .as_string(),
+            ),
+        ),
+        ("RUNTIPI_APP_DATA_PATH", Some(app_data_path.unwrap_or(root_folder.display().to_string()))),
+        ("POSTGRES_HOST", Some("runtipi-db".to_string())),
+        (
+            "POSTGRES_PORT",
+            Some(parsed_json.postgres_port.unwrap_or(StringOrInt::from(DEFAULT_POSTGRES_PORT)).as_string()),
+        ),
+        ("POSTGRES_DBNAME", Some("tipi".to_string())),
+        ("POSTGRES_USERNAME", Some("tipi".to_string())),
+        ("POSTGRES_PASSWORD", Some(postgres_password)),
+        ("RABBITMQ_HOST", Some("runtipi-queue".to_string())),
+        ("RABBITMQ_USERNAME", Some("tipi".to_string())),
+        ("RABBITMQ_PASSWORD", Some(rabbitmq_password)),
+        ("DOMAIN", Some(parsed_json.domain.unwrap_or(DEFAULT_DOMAIN.to_string()))),
+        ("LOCAL_DOMAIN", Some(parsed_json.local_domain.unwrap_or(DEFAULT_LOCAL_DOMAIN.to_string()))),
+        (
+            "RUNTIPI_FORWARD_AUTH_URL",
+            Some(parsed_json.forward_auth_url.unwrap_or(DEFAULT_FORWARD_AUTH_URL.to_string())),
+        ),
+        ("LOG_LEVEL", Some(parsed_json.log_level.unwrap_or("info".to_string()))),
+        // Only needed when the dashboard can't be reached through the internal address, see `api_base_urls`
+        ("RUNTIPI_API_URL", parsed_json.api_url),
+        ("RUNTIPI_API_CA_CERT", parsed_json.api_ca_cert),
+        (
+            "RUNTIPI_API_CONNECT_TIMEOUT",
+            parsed_json.api_connect_timeout.map(|timeout| timeout.to_string()),
+        ),
+        ("RUNTIPI_API_TIMEOUT", parsed_json.api_timeout.map(|timeout| timeout.to_string())),
+        ("RUNTIPI_API_RETRIES", parsed_json.api_retries.map(|retries| retries.to_string())),
+    ];
+
+    // Later sources take precedence: generated keys, then user-config/.env, then --env-file
+    let mut managed = vec![];
+    for (key, value) in generated {
+        match value {
+            Some(value) => managed.push((key.to_string(), value)),
+            None => document.remove(key),
+        }
+    }
+
+    let user_env_file_path = user_env_file_path(root_folder);
+    if user_env_file_path.exists() {
+        managed.extend(read_env_entries(&user_env_file_path, &mut warnings)?);
+    }
 
     if let Some(custom_env_file_path) = custom_env_file_path {
-        let custom_env_file = std::fs::read_to_string(&custom_env_file_path)?;
+        managed.extend(read_env_entries(custom_env_file_path, &mut warnings)?);
+    }
 
-        let custom_env_map = env_string_to_map(&custom_env_file);
+    let previous = read_generated_snapshot(root_folder);
+    let values = apply_managed_values(&mut document, &managed, &previous, &mut warnings);
 
-        let mut merged_env_map = new_env_map.clone();
+    Ok(RenderedEnv {
+        current: env_file,
+        content: document.to_string(),
+        values,
+        warnings,
+    })
+}
 
-        for (key, value) in custom_env_map {
-            merged_env_map.insert(key.clone(), value);
+/**
+* Set the values managed by the CLI in `document` and return them. `previous` holds the values written by the last
+* start, to tell a manual edit of .env from a change in settings.json and to drop keys whose source is gone.
+*/
+pub fn apply_managed_values(
+    document: &mut EnvDocument,
+    managed: &[(String, String)],
+    previous: &BTreeMap<String, String>,
+    warnings: &mut Vec<String>,
+) -> BTreeMap<String, String> {
+    let values: BTreeMap<String, String> = managed.iter().cloned().collect();
+
+    // New keys are appended in the order of their first source, with the value of the last one
+    let mut seen = HashSet::new();
+    for (key, _) in managed {
+        if !seen.insert(key) {
+            continue;
         }
 
-        let merged_env_string = env_map_to_string(&merged_env_map);
+        let value = &values[key];
+        let current = document.get(key);
+        let edited = matches!((current, previous.get(key)), (Some(current), Some(previous)) if current != previous);
 
-        std::fs::write(&env_file_path, merged_env_string)?;
-    } else {
-        let new_env_string = env_map_to_string(&new_env_map);
+        if edited && current != Some(value.as_str()) {
+            warnings.push(format!(
+                "{} was edited in .env and is replaced by its generated value, set it in user-config/.env to keep your change",
+                key
+            ));
+        }
+
+        document.set(key, value);
+    }
 
-        std::fs::write(&env_file_path, new_env_string)?;
+    // Keys written by a previous start whose source is gone, unless they were edited since
+    for (key, value) in previous {
+        if !values.contains_key(key) && document.get(key) == Some(value.as_str()) {
+            document.remove(key);
+        }
     }
 
-    Ok(())
+    values
+}
+
+/// The entries of an env file, invalid lines are added to `warnings`
+fn read_env_entries(path: &Path, warnings: &mut Vec<String>) -> Result<Vec<(String, String)>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    let (entries, errors) = dotenv::parse_lenient(&content);
+
+    warnings.extend(errors.iter().map(|error| format!("{}: {}", path.display(), error)));
+
+    Ok(entries)
 }
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::utils::schemas::FormField;
+
+/// Install options understood by the dashboard that are not part of the app form fields
+const RESERVED_KEYS: [&str; 5] = ["exposed", "exposedLocal", "openPort", "domain", "isVisibleOnGuestDashboard"];
+
+/// Parse a `key=value` pair passed on the command line
+pub fn parse_key_value(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+        _ => Err(format!("Invalid value '{}', expected the format KEY=VALUE", raw)),
+    }
+}
+
+fn value_as_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn validate_field(field: &FormField, raw: &str) -> Result<Value, String> {
+    let label = format!("{} ({})", field.label, field.env_variable);
+
+    if let Some(options) = &field.options {
+        if !options.iter().any(|option| option.value == raw) {
+            let values = options.iter().map(|option| option.value.as_str()).collect::<Vec<&str>>().join(", ");
+            return Err(format!("{} must be one of: {}", label, values));
+        }
+    }
+
+    let value = match field.field_type.as_str() {
+        "number" => {
+            let number = raw.parse::<i64>().map_err(|_| format!("{} must be a number", label))?;
+
+            if field.min.is_some_and(|min| number < min) || field.max.is_some_and(|max| number > max) {
+                return Err(format!(
+                    "{} must be between {} and {}",
+                    label,
+                    field.min.unwrap_or(i64::MIN),
+                    field.max.unwrap_or(i64::MAX)
+                ));
+            }
+
+            Value::from(number)
+        }
+        "boolean" => match raw {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => return Err(format!("{} must be true or false", label)),
+        },
+        _ => {
+            let length = raw.chars().count() as i64;
+
+            if field.min.is_some_and(|min| length < min) {
+                return Err(format!("{} must be at least {} characters long", label, field.min.unwrap_or_default()));
+            }
+            if field.max.is_some_and(|max| length > max) {
+                return Err(format!("{} must be at most {} characters long", label, field.max.unwrap_or_default()));
+            }
+
+            Value::String(raw.to_string())
+        }
+    };
+
+    if let Some(pattern) = &field.regex {
+        let regex = regex::Regex::new(pattern).map_err(|e| format!("{} has an invalid regex in config.json: {}", label, e))?;
+
+        if !regex.is_match(raw) {
+            return Err(field
+                .pattern_error
+                .as_ref()
+                .map(|error| format!("{}: {}", label, error))
+                .unwrap_or(format!("{} does not match the pattern {}", label, pattern)));
+        }
+    }
+
+    Ok(value)
+}
+
+/**
+* Validate user provided values against the form fields of an app and convert them to the JSON
+* types expected by the dashboard. Every problem is collected instead of stopping at the first one.
+*/
+pub fn validate_form_values(fields: &[FormField], values: &BTreeMap<String, Value>) -> Result<Map<String, Value>, Vec<String>> {
+    let mut form = Map::new();
+    let mut errors = vec![];
+
+    for (key, value) in values {
+        if RESERVED_KEYS.contains(&key.as_str()) {
+            let value = match value.as_str() {
+                Some("true") => Value::Bool(true),
+                Some("false") => Value::Bool(false),
+                _ => value.clone(),
+            };
+            form.insert(key.clone(), value);
+        } else if !fields.iter().any(|field| &field.env_variable == key) {
+            errors.push(format!("Unknown field {}", key));
+        }
+    }
+
+    for field in fields {
+        match values.get(&field.env_variable) {
+            Some(value) => match validate_field(field, &value_as_string(value)) {
+                Ok(value) => {
+                    form.insert(field.env_variable.clone(), value);
+                }
+                Err(err) => errors.push(err),
+            },
+            // Random fields are generated by the dashboard when left empty
+            None if field.required && field.field_type != "random" => errors.push(format!("{} ({}) is required", field.label, field.env_variable)),
+            None => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(form)
+    } else {
+        Err(errors)
+    }
+}
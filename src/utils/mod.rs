@@ -1,12 +1,35 @@
 pub mod api;
+pub mod apps;
+pub mod backups;
 pub mod constants;
+pub mod context;
+pub mod docker;
+pub mod dotenv;
 pub mod env;
+pub mod form;
 pub mod release;
+pub mod repos;
 pub mod schemas;
 pub mod seed;
+pub mod settings;
+pub mod store;
 pub mod system;
+pub mod transport;
+pub mod validation;
 
 #[cfg(test)]
 mod tests {
+    mod api;
+    mod apps;
+    mod backups;
+    mod context;
+    mod dotenv;
+    mod env;
+    mod form;
     mod release;
+    mod repos;
+    mod settings;
+    mod store;
+    mod transport;
+    mod validation;
 }
@@ -1,8 +1,4 @@
-use std::{
-    env::current_dir,
-    fs::File,
-    io::{Error, ErrorKind},
-};
+use std::{env::current_dir, fs::File, io::Error};
 
 use self_update::{self_replace::self_replace, update::Release};
 use serde::Deserialize;
@@ -40,20 +36,18 @@ pub fn get_latest_release() -> Result<String, Error> {
             if response.status().is_success() {
                 let latest = response.json::<GithubRelease>();
 
-                return match latest {
+                match latest {
                     Ok(latest) => Ok(latest.tag_name[1..].to_string()),
-                    Err(e) => {
-                        return Err(Error::new(ErrorKind::Other, format!("Failed to parse latest release: {:?}", e)));
-                    }
-                };
+                    Err(e) => Err(Error::other(format!("Failed to parse latest release: {:?}", e))),
+                }
             } else {
-                return Err(Error::new(
-                    ErrorKind::Other,
-                    format!("Failed to fetch latest release. Status code: {}", response.status()),
-                ));
+                Err(Error::other(format!(
+                    "Failed to fetch latest release. Status code: {}",
+                    response.status()
+                )))
             }
         }
-        Err(e) => Err(Error::new(ErrorKind::Other, format!("Error sending request: {:?}", e))),
+        Err(e) => Err(Error::other(format!("Error sending request: {:?}", e))),
     }
 }
 
@@ -62,11 +56,9 @@ pub fn get_all_releases() -> Result<Vec<Release>, Error> {
         .repo_owner("runtipi")
         .repo_name("cli")
         .build()
-        .map_err(|e| Error::new(ErrorKind::Other, format!("Failed to find releases from GitHub: {:?}", e)))?;
+        .map_err(|e| Error::other(format!("Failed to find releases from GitHub: {:?}", e)))?;
 
-    let fetch_result = releases
-        .fetch()
-        .map_err(|e| Error::new(ErrorKind::Other, format!("Failed to fetch releases: {:?}", e)))?;
+    let fetch_result = releases.fetch().map_err(|e| Error::other(format!("Failed to fetch releases: {:?}", e)))?;
 
     Ok(fetch_result)
 }
@@ -79,10 +71,10 @@ pub fn download_release_and_self_relace(release: &Release) -> Result<(), Error>
     let asset = match asset {
         Some(asset) => asset,
         None => {
-            return Err(Error::new(
-                ErrorKind::Other,
-                format!("No asset found for {} {} on release {}", arch, "linux", release.version),
-            ));
+            return Err(Error::other(format!(
+                "No asset found for {} {} on release {}",
+                arch, "linux", release.version
+            )));
         }
     };
 
@@ -95,7 +87,7 @@ pub fn download_release_and_self_relace(release: &Release) -> Result<(), Error>
     self_update::Download::from_url(&asset.download_url)
         .set_header(reqwest::header::ACCEPT, "application/octet-stream".parse().unwrap())
         .download_to(&tmp_tarball)
-        .map_err(|e| Error::new(ErrorKind::Other, format!("Failed to download release: {:?}", e)))?;
+        .map_err(|e| Error::other(format!("Failed to download release: {:?}", e)))?;
 
     std::process::Command::new("tar")
         .arg("-xzf")
@@ -106,7 +98,7 @@ pub fn download_release_and_self_relace(release: &Release) -> Result<(), Error>
 
     // asset.name with no extension
     let bin_name = asset.name.split(".").collect::<Vec<&str>>()[0];
-    let new_executable_path = current_dir.join(&bin_name);
+    let new_executable_path = current_dir.join(bin_name);
 
     std::process::Command::new("chmod").arg("+x").arg(&new_executable_path);
 
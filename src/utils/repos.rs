@@ -0,0 +1,102 @@
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::utils::schemas::{AppStoreRepo, SettingsSchema};
+
+fn settings_path(root_folder: &Path) -> std::path::PathBuf {
+    root_folder.join("state").join("settings.json")
+}
+
+/// The app store repositories configured in `settings.json`
+pub fn get_configured_repos(root_folder: &Path) -> Result<Vec<AppStoreRepo>, Error> {
+    let json_string = fs::read_to_string(settings_path(root_folder)).unwrap_or("{}".to_string());
+    let settings: SettingsSchema = serde_json::from_str(&json_string)?;
+
+    Ok(settings.app_stores.unwrap_or_default())
+}
+
+/// Write the repositories to `settings.json`, keeping every other setting untouched
+pub fn save_configured_repos(root_folder: &Path, repos: &[AppStoreRepo]) -> Result<(), Error> {
+    let path = settings_path(root_folder);
+    let json_string = fs::read_to_string(&path).unwrap_or("{}".to_string());
+
+    let mut settings: Value = serde_json::from_str(&json_string)?;
+    let object = settings.as_object_mut().ok_or(Error::other("settings.json must contain a JSON object"))?;
+
+    if repos.is_empty() {
+        object.remove("appStores");
+    } else {
+        object.insert("appStores".to_string(), serde_json::to_value(repos)?);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, format!("{}\n", serde_json::to_string_pretty(&settings)?))
+}
+
+/// Derive a folder name from a repository url, eg: `https://github.com/me/my-store.git` -> `my-store`
+pub fn repo_id_from_url(url: &str) -> String {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".git");
+
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+pub fn is_valid_repo_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn git(args: &[&str], repo_path: Option<&Path>) -> Result<String, Error> {
+    let mut command = Command::new("git");
+
+    if let Some(repo_path) = repo_path {
+        command.arg("-C").arg(repo_path);
+    }
+
+    let output = command.args(args).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(Error::other(format!("git {} failed: {}", args.join(" "), stderr)));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn git_clone(url: &str, branch: Option<&str>, destination: &Path) -> Result<(), Error> {
+    let destination = destination.to_string_lossy();
+    let mut args = vec!["clone", "--depth", "1"];
+
+    if let Some(branch) = branch {
+        args.extend(["--branch", branch]);
+    }
+    args.extend([url, &destination]);
+
+    git(&args, None).map(|_| ())
+}
+
+pub fn git_pull(repo_path: &Path) -> Result<(), Error> {
+    git(&["pull", "--ff-only"], Some(repo_path)).map(|_| ())
+}
+
+/// The short hash of the checked out commit
+pub fn git_head(repo_path: &Path) -> Result<String, Error> {
+    git(&["rev-parse", "--short", "HEAD"], Some(repo_path))
+}
+
+pub fn git_remote_url(repo_path: &Path) -> Result<String, Error> {
+    git(&["remote", "get-url", "origin"], Some(repo_path))
+}
@@ -59,4 +59,185 @@ pub struct SettingsSchema {
 
     #[serde(rename = "logLevel")]
     pub log_level: Option<String>,
+
+    #[serde(rename = "apiUrl")]
+    pub api_url: Option<String>,
+
+    #[serde(rename = "apiCaCert")]
+    pub api_ca_cert: Option<String>,
+
+    #[serde(rename = "apiConnectTimeout")]
+    pub api_connect_timeout: Option<u64>,
+
+    #[serde(rename = "apiTimeout")]
+    pub api_timeout: Option<u64>,
+
+    #[serde(rename = "apiRetries")]
+    pub api_retries: Option<u64>,
+
+    #[serde(rename = "appStores")]
+    pub app_stores: Option<Vec<AppStoreRepo>>,
+}
+
+/// An app store repository cloned in `repos/<id>`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppStoreRepo {
+    pub id: String,
+
+    pub url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormFieldOption {
+    pub label: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormField {
+    #[serde(rename = "type")]
+    pub field_type: String,
+
+    pub label: String,
+
+    pub env_variable: String,
+
+    #[serde(default)]
+    pub required: bool,
+
+    pub min: Option<i64>,
+
+    pub max: Option<i64>,
+
+    pub regex: Option<String>,
+
+    pub pattern_error: Option<String>,
+
+    pub options: Option<Vec<FormFieldOption>>,
+}
+
+/// The `config.json` of an app, either in an app store repository or in the installed app folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfigSchema {
+    pub id: String,
+
+    pub name: String,
+
+    #[serde(default)]
+    pub version: String,
+
+    #[serde(default)]
+    pub tipi_version: i64,
+
+    pub port: Option<i64>,
+
+    #[serde(default)]
+    pub exposable: bool,
+
+    #[serde(default)]
+    pub short_desc: String,
+
+    pub author: Option<String>,
+
+    pub source: Option<String>,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    #[serde(default)]
+    pub form_fields: Vec<FormField>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallAppBody {
+    pub remove_backups: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAppBody {
+    pub perform_backup: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreAppBody {
+    pub filename: String,
+}
+
+/// `GET /api/apps/installed`
+#[derive(Deserialize, Debug)]
+pub struct InstalledAppsResponse {
+    pub installed: Vec<ApiInstalledApp>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiInstalledApp {
+    pub app: ApiApp,
+    pub info: Option<ApiAppInfo>,
+    pub metadata: Option<ApiAppMetadata>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiApp {
+    #[serde(alias = "urn")]
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub version: i64,
+    #[serde(default)]
+    pub exposed: bool,
+    pub domain: Option<String>,
+    pub port: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiAppInfo {
+    #[serde(default)]
+    pub version: String,
+    pub port: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiAppMetadata {
+    pub latest_version: Option<i64>,
+    pub latest_docker_version: Option<String>,
+}
+
+/// `GET /api/system/load`
+#[derive(Deserialize, Debug, Default)]
+pub struct SystemLoad {
+    #[serde(default)]
+    pub cpu: CpuLoad,
+    #[serde(default)]
+    pub disk: ResourceUsage,
+    #[serde(default)]
+    pub memory: ResourceUsage,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CpuLoad {
+    pub load: Option<f64>,
+}
+
+/// Sizes in bytes
+#[derive(Deserialize, Debug, Default)]
+pub struct ResourceUsage {
+    pub total: Option<f64>,
+    pub used: Option<f64>,
+}
+
+/// `GET /api/system/version`
+#[derive(Deserialize, Debug)]
+pub struct VersionResponse {
+    #[serde(alias = "version")]
+    pub current: String,
+    pub latest: Option<String>,
 }
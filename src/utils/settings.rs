@@ -0,0 +1,203 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::utils::schemas::AppStoreRepo;
+use crate::utils::store::levenshtein;
+use crate::utils::system::is_local_ip;
+use crate::utils::validation::{validate_domain, validate_ip, validate_log_level, validate_port};
+
+/// The keys of settings.json read by the CLI, see `SettingsSchema`
+const CLI_KEYS: &[&str] = &[
+    "listenIp",
+    "port",
+    "sslPort",
+    "storagePath",
+    "appDataPath",
+    "postgresPort",
+    "domain",
+    "localDomain",
+    "forwardAuthUrl",
+    "logLevel",
+    "apiUrl",
+    "apiCaCert",
+    "apiConnectTimeout",
+    "apiTimeout",
+    "apiRetries",
+    "appStores",
+];
+
+/// Keys only read by the dashboard, they are not unknown and the ones with a known format are validated too
+const DASHBOARD_KEYS: &[&str] = &[
+    "dnsIp",
+    "internalIp",
+    "demoMode",
+    "guestDashboard",
+    "allowAutoThemes",
+    "allowErrorMonitoring",
+    "persistTraefikConfig",
+    "appsRepoUrl",
+    "appsRepoId",
+    "timeZone",
+    "eventsTimeout",
+    "advancedSettings",
+    "experimental",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsProblem {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SettingsProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// The line and column of `offset`, both starting at 1
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// Where a top-level key is written, the start of the file when it can't be found
+fn key_position(content: &str, key: &str) -> (usize, usize) {
+    Regex::new(&format!(r#""{}"\s*:"#, regex::escape(key)))
+        .ok()
+        .and_then(|regex| regex.find(content))
+        .map(|found| position(content, found.start()))
+        .unwrap_or((1, 1))
+}
+
+fn suggestion(key: &str) -> Option<&'static str> {
+    CLI_KEYS
+        .iter()
+        .chain(DASHBOARD_KEYS)
+        .map(|known| (*known, levenshtein(&key.to_lowercase(), &known.to_lowercase())))
+        .filter(|(_, distance)| *distance <= 3)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(known, _)| known)
+}
+
+fn check_string(value: &Value, check: impl Fn(&str) -> Result<(), String>) -> Result<(), String> {
+    match value {
+        Value::String(s) => check(s),
+        _ => Err(format!("expected a string, got {}", value)),
+    }
+}
+
+fn check_value(key: &str, value: &Value) -> Result<(), String> {
+    match key {
+        "listenIp" | "internalIp" => check_string(value, validate_ip),
+        "port" | "sslPort" | "postgresPort" => match value {
+            Value::Number(port) => validate_port(&port.to_string()),
+            _ => check_string(value, validate_port),
+        },
+        "domain" | "localDomain" => check_string(value, validate_domain),
+        "logLevel" => check_string(value, validate_log_level),
+        "appDataPath" | "storagePath" => check_string(value, |path| match Path::new(path).is_absolute() && Path::new(path).exists() {
+            true => Ok(()),
+            false => Err(format!("'{}' must be an absolute path to an existing folder", path)),
+        }),
+        "forwardAuthUrl" | "apiUrl" => check_string(value, |url| match url.starts_with("http://") || url.starts_with("https://") {
+            true => Ok(()),
+            false => Err(format!("'{}' is not a valid url, it must start with http:// or https://", url)),
+        }),
+        "apiCaCert" => check_string(value, |path| match Path::new(path).is_file() {
+            true => Ok(()),
+            false => Err(format!("'{}' is not a file", path)),
+        }),
+        "apiConnectTimeout" | "apiTimeout" | "apiRetries" => match value.as_u64() {
+            Some(_) => Ok(()),
+            None => Err(format!("expected a positive whole number, got {}", value)),
+        },
+        "appStores" => serde_json::from_value::<Vec<AppStoreRepo>>(value.clone())
+            .map(|_| ())
+            .map_err(|e| format!("expected a list of {{ \"id\", \"url\", \"branch\" }}: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+/**
+* Problems that don't stop runtipi from starting. The listen address may be assigned after the check runs, eg: a
+* keepalived virtual IP or a bridge brought up later, so it is only a warning when it is not on this machine yet.
+*/
+fn check_warning(key: &str, value: &Value) -> Option<String> {
+    match (key, value.as_str().map(|ip| ip.parse::<IpAddr>())) {
+        ("listenIp", Some(Ok(ip))) if !is_local_ip(&ip) => Some(format!(
+            "\"listenIp\": {} is not an address of this machine, docker can't listen on it until it is assigned",
+            ip
+        )),
+        _ => None,
+    }
+}
+
+/// Check settings.json and report every problem instead of stopping at the first one
+pub fn validate_settings(content: &str) -> Vec<SettingsProblem> {
+    let problem = |severity: Severity, (line, column): (usize, usize), message: String| SettingsProblem {
+        severity,
+        line,
+        column,
+        message,
+    };
+
+    let settings: Map<String, Value> = match serde_json::from_str::<Value>(content) {
+        Ok(Value::Object(settings)) => settings,
+        Ok(_) => return vec![problem(Severity::Error, (1, 1), "settings.json must contain a JSON object".to_string())],
+        Err(e) => return vec![problem(Severity::Error, (e.line(), e.column()), format!("invalid JSON: {}", e))],
+    };
+
+    let mut problems = vec![];
+
+    for (key, value) in &settings {
+        let at = key_position(content, key);
+
+        if !CLI_KEYS.contains(&key.as_str()) && !DASHBOARD_KEYS.contains(&key.as_str()) {
+            let message = match suggestion(key) {
+                Some(known) => format!("unknown key \"{}\", did you mean \"{}\"?", key, known),
+                None => format!("unknown key \"{}\"", key),
+            };
+            problems.push(problem(Severity::Warning, at, message));
+            continue;
+        }
+
+        // Every setting is optional, null is the same as not set
+        if value.is_null() {
+            continue;
+        }
+
+        if let Err(err) = check_value(key, value) {
+            problems.push(problem(Severity::Error, at, format!("invalid \"{}\": {}", key, err)));
+        } else if let Some(warning) = check_warning(key, value) {
+            problems.push(problem(Severity::Warning, at, warning));
+        }
+    }
+
+    if settings.contains_key("storagePath") {
+        let at = key_position(content, "storagePath");
+        problems.push(problem(
+            Severity::Warning,
+            at,
+            "\"storagePath\" is deprecated, use \"appDataPath\"".to_string(),
+        ));
+    }
+
+    problems.sort_by_key(|problem| (problem.line, problem.column));
+
+    problems
+}
@@ -0,0 +1,169 @@
+use std::io::Error;
+use std::path::{Path, PathBuf};
+
+use crate::utils::apps::{folder_name, read_app_config, sorted_sub_folders, InstalledApp};
+use crate::utils::schemas::AppConfigSchema;
+
+#[derive(Debug, Clone)]
+pub struct StoreApp {
+    /// The name of the app folder in the repository, eg: `nextcloud`
+    pub name: String,
+    /// The name of the repository folder under `repos/`
+    pub store: String,
+    pub path: PathBuf,
+    pub config: AppConfigSchema,
+}
+
+impl StoreApp {
+    /// The id to install the app with, eg: `nextcloud:migrated`
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.name, self.store)
+    }
+
+    /// The long description, app stores keep it in `metadata/description.md` next to the `config.json`
+    pub fn description(&self) -> String {
+        std::fs::read_to_string(self.path.join("metadata").join("description.md")).unwrap_or(self.config.description.clone())
+    }
+
+    /// Whether the app is installed, legacy installs (`apps/<app>`) have no store so they match the app in any repository
+    pub fn is_installed(&self, installed_apps: &[InstalledApp]) -> bool {
+        installed_apps
+            .iter()
+            .any(|app| app.name == self.name && app.store.as_ref().is_none_or(|store| *store == self.store))
+    }
+}
+
+/// Every app with a valid `config.json` in the cloned repositories, sorted by repository then by name
+pub fn get_store_apps(root_folder: &Path) -> Result<Vec<StoreApp>, Error> {
+    let repos_folder = root_folder.join("repos");
+
+    if !repos_folder.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut apps = vec![];
+
+    for repo in sorted_sub_folders(&repos_folder)? {
+        let store = folder_name(&repo);
+
+        for app_folder in sorted_sub_folders(&repo.join("apps")).unwrap_or_default() {
+            if let Some(config) = read_app_config(&app_folder.join("config.json")) {
+                apps.push(StoreApp {
+                    name: folder_name(&app_folder),
+                    store: store.clone(),
+                    path: app_folder,
+                    config,
+                });
+            }
+        }
+    }
+
+    Ok(apps)
+}
+
+/// The number of single character edits needed to turn `a` into `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/**
+* Score how well `term` matches an app, `None` when it does not match at all. The name is matched exactly, by prefix,
+* by substring, with the letters in order (`nxtcld`) or with a couple of typos, then categories and descriptions
+* are matched by substring.
+*/
+pub fn match_score(term: &str, app: &StoreApp) -> Option<u32> {
+    let term = term.trim().to_lowercase();
+
+    if term.is_empty() {
+        return Some(0);
+    }
+
+    let names = [app.name.to_lowercase(), app.config.name.to_lowercase()];
+    let max_typos = if term.chars().count() > 4 { 2 } else { 1 };
+
+    let name_score = names.iter().filter_map(|name| {
+        if *name == term {
+            Some(100)
+        } else if name.starts_with(&term) {
+            Some(80)
+        } else if name.contains(&term) {
+            Some(60)
+        } else if levenshtein(&term, name) <= max_typos {
+            Some(50)
+        } else if is_subsequence(&term, name) {
+            Some(40)
+        } else {
+            None
+        }
+    });
+
+    let category_score = app
+        .config
+        .categories
+        .iter()
+        .any(|category| category.to_lowercase().contains(&term))
+        .then_some(30);
+    let short_desc_score = app.config.short_desc.to_lowercase().contains(&term).then_some(20);
+    let description_score = app.config.description.to_lowercase().contains(&term).then_some(10);
+
+    name_score.chain(category_score).chain(short_desc_score).chain(description_score).max()
+}
+
+/// The apps matching `term`, best matches first
+pub fn search_store_apps<'a>(apps: &'a [StoreApp], term: &str) -> Vec<&'a StoreApp> {
+    let mut matches = apps
+        .iter()
+        .filter_map(|app| match_score(term, app).map(|score| (score, app)))
+        .collect::<Vec<(u32, &StoreApp)>>();
+
+    // The sort is stable so apps with the same score keep the repository order
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    matches.into_iter().map(|(_, app)| app).collect()
+}
+
+/// Find an app by `name:store` or by name alone, preferring `preferred_store` when several repositories provide it
+pub fn find_store_app<'a>(apps: &'a [StoreApp], id: &str, preferred_store: Option<&str>) -> Result<&'a StoreApp, String> {
+    let (name, store) = match id.split_once(':') {
+        Some((name, store)) => (name, Some(store)),
+        None => (id, None),
+    };
+
+    let matches = apps
+        .iter()
+        .filter(|app| app.name == name && store.is_none_or(|store| app.store == store))
+        .collect::<Vec<&StoreApp>>();
+
+    match matches.as_slice() {
+        [] => Err(format!("App {} not found in the app store repositories under repos/", id)),
+        [app] => Ok(app),
+        _ => matches
+            .iter()
+            .find(|app| Some(app.store.as_str()) == preferred_store)
+            .copied()
+            .ok_or(format!(
+                "App {} is provided by several app stores, use one of: {}",
+                id,
+                matches.iter().map(|app| app.id()).collect::<Vec<String>>().join(", ")
+            )),
+    }
+}
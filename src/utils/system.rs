@@ -1,7 +1,8 @@
 use hex::encode;
 
 use sha2::{Digest, Sha256};
-use std::io::{Error, ErrorKind, Write};
+use std::io::{Error, Write};
+use std::net::IpAddr;
 use std::path::Path;
 use std::{env, fs};
 use std::{fs::File, path::PathBuf};
@@ -26,14 +27,22 @@ pub fn get_internal_ip() -> String {
     }
 }
 
+/// Whether the host can listen on `ip`, the unspecified address listens on every interface
+pub fn is_local_ip(ip: &IpAddr) -> bool {
+    ip.is_unspecified()
+        || ip.is_loopback()
+        || netdev::get_interfaces()
+            .iter()
+            .any(|iface| iface.ipv4.iter().any(|net| IpAddr::V4(net.addr()) == *ip) || iface.ipv6.iter().any(|net| IpAddr::V6(net.addr()) == *ip))
+}
+
 pub fn get_seed(root_folder: &Path) -> Result<String, Error> {
     let seed_file_path = root_folder.join("state").join("seed");
     let seed = std::fs::read_to_string(&seed_file_path);
 
     match seed {
         Ok(seed) => Ok(seed),
-        Err(_) => Err(Error::new(
-            ErrorKind::Other,
+        Err(_) => Err(Error::other(
             "Unable to read the seed file. Please run the start command first.".to_string(),
         )),
     }
@@ -53,8 +62,7 @@ pub fn ensure_docker() -> Result<(), Error> {
     match output {
         Ok(output) => {
             if !output.status.success() {
-                return Err(Error::new(
-                    ErrorKind::Other,
+                return Err(Error::other(
                     "Docker is not installed or user has not the right permissions. See https://docs.docker.com/engine/install/ for more information"
                         .to_string(),
                 ));
@@ -67,15 +75,13 @@ pub fn ensure_docker() -> Result<(), Error> {
             let version_number: Vec<&str> = version_parts[2].split(".").collect();
 
             if version_number[0].parse::<i32>().unwrap() < 28 {
-                return Err(Error::new(
-                    ErrorKind::Other,
+                return Err(Error::other(
                     "Docker version 28 or higher is required. See https://docs.docker.com/engine/install/ for more information".to_string(),
                 ));
             }
         }
         Err(_) => {
-            return Err(Error::new(
-                ErrorKind::Other,
+            return Err(Error::other(
                 "Docker is not installed or user has not the right permissions. See https://docs.docker.com/engine/install/ for more information"
                     .to_string(),
             ));
@@ -87,15 +93,13 @@ pub fn ensure_docker() -> Result<(), Error> {
     match output {
         Ok(output) => {
             if !output.status.success() {
-                return Err(Error::new(
-                    ErrorKind::Other,
+                return Err(Error::other(
                     "Docker compose plugin is not installed. See https://docs.docker.com/compose/install/linux/ for more information".to_string(),
                 ));
             }
         }
         Err(_) => {
-            return Err(Error::new(
-                ErrorKind::Other,
+            return Err(Error::other(
                 "Docker compose plugin is not installed. See https://docs.docker.com/compose/install/linux/ for more information".to_string(),
             ));
         }
@@ -152,10 +156,10 @@ pub fn ensure_file_permissions() -> Result<(), Error> {
             let chmod_status = std::process::Command::new("chmod").arg("-Rf").arg(perms).arg(&full_path).output()?;
 
             if !chmod_status.status.success() {
-                return Err(Error::new(
-                    ErrorKind::Other,
-                    format!("{} has incorrect permissions. Please run the CLI as root to fix this.", path),
-                ));
+                return Err(Error::other(format!(
+                    "{} has incorrect permissions. Please run the CLI as root to fix this.",
+                    path
+                )));
             }
         }
     }
@@ -0,0 +1,331 @@
+#[cfg(test)]
+mod test_server_message {
+    use crate::utils::api::server_message;
+
+    #[test]
+    fn test_json_message() {
+        assert_eq!(server_message(r#"{"message": "App not found"}"#), Some("App not found".to_string()));
+        assert_eq!(
+            server_message(r#"{"message": ["port must be a number", "domain is required"]}"#),
+            Some("port must be a number, domain is required".to_string())
+        );
+        assert_eq!(server_message(r#"{"statusCode": 500}"#), None);
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(server_message("Bad Gateway\n"), Some("Bad Gateway".to_string()));
+        assert_eq!(server_message("<html><body>502</body></html>"), None);
+        assert_eq!(server_message(""), None);
+    }
+}
+
+#[cfg(test)]
+mod test_create_token {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use jsonwebtoken::{decode, DecodingKey, Validation};
+
+    use crate::utils::api::{create_token, Claims, TOKEN_AUDIENCE, TOKEN_LIFETIME};
+
+    fn decode_token(token: &str, secret: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[TOKEN_AUDIENCE]);
+
+        decode::<Claims>(token, &DecodingKey::from_secret(secret.as_ref()), &validation).map(|data| data.claims)
+    }
+
+    #[test]
+    fn test_claims() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let token = create_token("secret", Some("app:start")).unwrap();
+
+        let claims = decode_token(&token, "secret").unwrap();
+
+        assert_eq!(claims.sub, "cli");
+        assert_eq!(claims.aud, TOKEN_AUDIENCE);
+        assert_eq!(claims.scope.as_deref(), Some("app:start"));
+        assert!(claims.iat >= now && claims.iat <= now + 1);
+        assert_eq!(claims.exp - claims.iat, TOKEN_LIFETIME.as_secs());
+    }
+
+    #[test]
+    fn test_unique_jti() {
+        let first = decode_token(&create_token("secret", None).unwrap(), "secret").unwrap();
+        let second = decode_token(&create_token("secret", None).unwrap(), "secret").unwrap();
+
+        assert_eq!(first.scope, None);
+        assert_eq!(first.jti.len(), 32);
+        assert_ne!(first.jti, second.jti);
+    }
+
+    #[test]
+    fn test_rejected_with_wrong_secret_or_audience() {
+        let token = create_token("secret", None).unwrap();
+
+        assert!(decode_token(&token, "other").is_err());
+
+        let mut validation = Validation::default();
+        validation.set_audience(&["someone-else"]);
+        assert!(decode::<Claims>(&token, &DecodingKey::from_secret(b"secret"), &validation).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_api_base_urls {
+    use crate::utils::api::{api_base_urls, ClientOptions, RuntipiClient};
+    use crate::utils::context::Context;
+    use crate::utils::env::EnvMap;
+
+    fn env_map(entries: &[(&str, &str)]) -> EnvMap {
+        entries.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_internal_address_then_domains() {
+        let env_map = env_map(&[
+            ("INTERNAL_IP", "192.168.1.10"),
+            ("NGINX_PORT", "8080"),
+            ("NGINX_PORT_SSL", "443"),
+            ("DOMAIN", "tipi.example.org"),
+            ("LOCAL_DOMAIN", "tipi.lan"),
+        ]);
+
+        assert_eq!(
+            api_base_urls(&env_map),
+            vec!["http://192.168.1.10:8080/api", "https://tipi.example.org/api", "https://tipi.lan/api"]
+        );
+    }
+
+    #[test]
+    fn test_skips_default_domain() {
+        let env_map = env_map(&[("DOMAIN", "example.com"), ("NGINX_PORT_SSL", "8443")]);
+
+        assert_eq!(api_base_urls(&env_map), vec!["http://localhost:80/api"]);
+    }
+
+    #[test]
+    fn test_explicit_api_url() {
+        let without_path = env_map(&[("RUNTIPI_API_URL", "https://tipi.example.org/"), ("DOMAIN", "other.org")]);
+        assert_eq!(api_base_urls(&without_path), vec!["https://tipi.example.org/api"]);
+
+        let with_path = env_map(&[("RUNTIPI_API_URL", "https://example.org/tipi/api")]);
+        assert_eq!(api_base_urls(&with_path), vec!["https://example.org/tipi/api"]);
+    }
+
+    #[test]
+    fn test_remote_host() {
+        let context = Context {
+            name: "homelab".to_string(),
+            api_url: "https://tipi.example.org:8443".to_string(),
+            jwt_secret: None,
+            token: None,
+            ca_cert: None,
+        };
+        let remote = RuntipiClient::from_context(&context, ClientOptions::default()).unwrap();
+        assert_eq!(remote.remote_host().as_deref(), Some("tipi.example.org"));
+
+        let local = RuntipiClient::from_env_map(&env_map(&[("INTERNAL_IP", "192.168.1.10")]), ClientOptions::default()).unwrap();
+        assert_eq!(local.remote_host(), None);
+    }
+}
+
+#[cfg(test)]
+mod test_retry_policy {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use reqwest::blocking::Client;
+    use reqwest::StatusCode;
+
+    use crate::utils::api::{ApiError, AppLifecycleRequest, ClientAuth, ClientOptions, RetryPolicy, RuntipiClient};
+    use crate::utils::transport::Transport;
+
+    struct MockResponse {
+        status: u16,
+        body: &'static str,
+        delay: Duration,
+    }
+
+    fn respond(status: u16, body: &'static str) -> MockResponse {
+        MockResponse {
+            status,
+            body,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Answer one connection per scripted response and record the request line and headers of each of them
+    fn mock_server(responses: Vec<MockResponse>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut head = String::new();
+                let mut content_length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                recorded.lock().unwrap().push(head);
+
+                thread::sleep(response.delay);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.status,
+                    response.body.len(),
+                    response.body
+                );
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn options(max_retries: u32, timeout: Duration) -> ClientOptions {
+        ClientOptions {
+            connect_timeout: Duration::from_secs(1),
+            timeout,
+            retry: RetryPolicy {
+                max_retries,
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(20),
+            },
+        }
+    }
+
+    fn client(url: String, options: ClientOptions) -> RuntipiClient {
+        let http_client = Client::builder()
+            .connect_timeout(options.connect_timeout)
+            .timeout(options.timeout)
+            .build()
+            .unwrap();
+
+        RuntipiClient::new(
+            vec![Transport::Proxy { url, client: http_client }],
+            ClientAuth::JwtSecret("secret".to_string()),
+            options,
+        )
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+
+        let delays = (0..5).map(|attempt| policy.backoff(attempt).as_millis()).collect::<Vec<u128>>();
+
+        assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+    }
+
+    #[test]
+    fn test_options_from_env_map() {
+        let env_map = [("RUNTIPI_API_TIMEOUT", "10"), ("RUNTIPI_API_RETRIES", "1")]
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        let options = ClientOptions::from_env_map(&env_map, None);
+        assert_eq!(options.timeout, Duration::from_secs(10));
+        assert_eq!(options.retry.max_retries, 1);
+        assert_eq!(options.connect_timeout, ClientOptions::default().connect_timeout);
+
+        // The --timeout flag wins over the .env file
+        assert_eq!(ClientOptions::from_env_map(&env_map, Some(3)).timeout, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_retries_while_the_dashboard_restarts() {
+        let (url, requests) = mock_server(vec![respond(503, ""), respond(502, ""), respond(200, r#"{"installed": []}"#)]);
+
+        let installed = client(url, options(3, Duration::from_secs(5))).installed_apps().unwrap();
+
+        assert!(installed.installed.is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert!(requests.lock().unwrap()[0].starts_with("GET /api/apps/installed "));
+        assert!(requests.lock().unwrap()[0].to_lowercase().contains("authorization: bearer "));
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        let (url, requests) = mock_server(vec![respond(503, ""), respond(503, ""), respond(503, r#"{"message": "Starting"}"#)]);
+
+        let err = client(url, options(2, Duration::from_secs(5)))
+            .app_lifecycle("whoami", &AppLifecycleRequest::Start)
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ApiError::Server {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                ..
+            }
+        ));
+        assert_eq!(err.to_string(), "Error code: 503 Service Unavailable - Starting");
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_client_errors_are_not_retried() {
+        let (url, requests) = mock_server(vec![respond(404, r#"{"message": "App whoami not found"}"#)]);
+
+        let err = client(url, options(3, Duration::from_secs(5)))
+            .app_lifecycle("whoami", &AppLifecycleRequest::Stop)
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Error code: 404 Not Found - App whoami not found");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_read_timeout_is_not_retried() {
+        let (url, requests) = mock_server(vec![MockResponse {
+            status: 200,
+            body: "{}",
+            delay: Duration::from_millis(500),
+        }]);
+
+        let err = client(url, options(3, Duration::from_millis(100)))
+            .app_lifecycle("whoami", &AppLifecycleRequest::Start)
+            .unwrap_err();
+
+        assert!(matches!(err, ApiError::Transport(_)));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_connection_refused_is_retried() {
+        // Bind then drop a listener to get a port nothing listens on
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/api", listener.local_addr().unwrap())
+        };
+
+        let err = client(url, options(2, Duration::from_secs(1))).health().unwrap_err();
+
+        assert!(matches!(err, ApiError::Unreachable(_)));
+    }
+}
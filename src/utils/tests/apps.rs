@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod test_get_installed_apps {
+    use std::fs;
+    use std::path::Path;
+
+    use crate::utils::apps::get_installed_apps;
+
+    fn create_app(root: &Path, relative_path: &str) {
+        let app_folder = root.join("apps").join(relative_path);
+        fs::create_dir_all(&app_folder).unwrap();
+        fs::write(app_folder.join("docker-compose.yml"), "services: {}").unwrap();
+    }
+
+    #[test]
+    fn test_no_apps_folder() {
+        let root = tempfile::tempdir().unwrap();
+
+        assert!(get_installed_apps(root.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_legacy_layout() {
+        let root = tempfile::tempdir().unwrap();
+        create_app(root.path(), "nextcloud");
+        create_app(root.path(), "adguard");
+
+        let ids = get_installed_apps(root.path())
+            .unwrap()
+            .into_iter()
+            .map(|app| app.id)
+            .collect::<Vec<String>>();
+
+        assert_eq!(ids, vec!["adguard", "nextcloud"]);
+    }
+
+    #[test]
+    fn test_app_store_layout() {
+        let root = tempfile::tempdir().unwrap();
+        create_app(root.path(), "migrated/nextcloud");
+        create_app(root.path(), "private/whoami");
+        fs::create_dir_all(root.path().join("apps").join("empty")).unwrap();
+
+        let ids = get_installed_apps(root.path())
+            .unwrap()
+            .into_iter()
+            .map(|app| app.id)
+            .collect::<Vec<String>>();
+
+        assert_eq!(ids, vec!["nextcloud:migrated", "whoami:private"]);
+    }
+}
+
+#[cfg(test)]
+mod test_select_app_ids {
+    use std::path::PathBuf;
+
+    use regex::Regex;
+
+    use crate::utils::apps::{glob_to_regex, select_app_ids, InstalledApp};
+
+    fn apps() -> Vec<InstalledApp> {
+        ["nextcloud:migrated", "nextcloud-aio:migrated", "whoami:private", "jellyfin"]
+            .iter()
+            .map(|id| InstalledApp {
+                id: id.to_string(),
+                name: id.split(':').next().unwrap().to_string(),
+                store: id.split(':').nth(1).map(|store| store.to_string()),
+                path: PathBuf::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_no_pattern_selects_all() {
+        assert_eq!(select_app_ids(&apps(), None).len(), 4);
+    }
+
+    #[test]
+    fn test_glob_matches_names_and_ids() {
+        let pattern = glob_to_regex("nextcloud*").unwrap();
+        assert_eq!(
+            select_app_ids(&apps(), Some(&pattern)),
+            vec!["nextcloud:migrated", "nextcloud-aio:migrated"]
+        );
+
+        let pattern = glob_to_regex("*:private").unwrap();
+        assert_eq!(select_app_ids(&apps(), Some(&pattern)), vec!["whoami:private"]);
+    }
+
+    #[test]
+    fn test_glob_escapes_regex_characters() {
+        let pattern = glob_to_regex("jelly.in").unwrap();
+        assert!(select_app_ids(&apps(), Some(&pattern)).is_empty());
+
+        let pattern = glob_to_regex("jelly?in").unwrap();
+        assert_eq!(select_app_ids(&apps(), Some(&pattern)), vec!["jellyfin"]);
+    }
+
+    #[test]
+    fn test_regex() {
+        let pattern = Regex::new("^(whoami|jellyfin)").unwrap();
+        assert_eq!(select_app_ids(&apps(), Some(&pattern)), vec!["whoami:private", "jellyfin"]);
+    }
+}
+
+#[cfg(test)]
+mod test_available_update {
+    use std::fs;
+    use std::path::Path;
+
+    use crate::utils::apps::get_installed_apps;
+    use crate::utils::env::EnvMap;
+
+    fn write_config(folder: &Path, version: &str, tipi_version: i64) {
+        fs::create_dir_all(folder).unwrap();
+        let config = format!(
+            r#"{{ "id": "whoami", "name": "Whoami", "version": "{}", "tipi_version": {} }}"#,
+            version, tipi_version
+        );
+        fs::write(folder.join("config.json"), config).unwrap();
+        fs::write(folder.join("docker-compose.yml"), "services: {}").unwrap();
+    }
+
+    #[test]
+    fn test_newer_version_in_repo() {
+        let root = tempfile::tempdir().unwrap();
+        write_config(&root.path().join("apps/private/whoami"), "1.0.0", 1);
+        write_config(&root.path().join("repos/private/apps/whoami"), "1.1.0", 3);
+
+        let app = get_installed_apps(root.path()).unwrap().remove(0);
+        let update = app.available_update(root.path(), &EnvMap::new()).unwrap();
+
+        assert_eq!(update.current_version, "1.0.0");
+        assert_eq!(update.latest_version, "1.1.0");
+        assert_eq!(update.latest_tipi_version, 3);
+    }
+
+    #[test]
+    fn test_up_to_date() {
+        let root = tempfile::tempdir().unwrap();
+        write_config(&root.path().join("apps/whoami"), "1.1.0", 3);
+        write_config(&root.path().join("repos/official/apps/whoami"), "1.1.0", 3);
+
+        let app = get_installed_apps(root.path()).unwrap().remove(0);
+        let mut env_map = EnvMap::new();
+        env_map.insert("APPS_REPO_ID".to_string(), "official".to_string());
+
+        assert!(app.available_update(root.path(), &env_map).is_none());
+    }
+
+    #[test]
+    fn test_store_app_not_compared_with_another_store() {
+        let root = tempfile::tempdir().unwrap();
+        write_config(&root.path().join("apps/migrated/whoami"), "1.0.0", 1);
+        write_config(&root.path().join("repos/community/apps/whoami"), "2.0.0", 5);
+
+        let app = get_installed_apps(root.path()).unwrap().remove(0);
+
+        assert!(app.available_update(root.path(), &EnvMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_missing_installed_config() {
+        let root = tempfile::tempdir().unwrap();
+        write_config(&root.path().join("apps/private/whoami"), "1.0.0", 1);
+        write_config(&root.path().join("repos/private/apps/whoami"), "1.1.0", 3);
+        fs::remove_file(root.path().join("apps/private/whoami/config.json")).unwrap();
+
+        let app = get_installed_apps(root.path()).unwrap().remove(0);
+
+        assert!(app.available_update(root.path(), &EnvMap::new()).is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_mentions_app {
+    use crate::utils::apps::mentions_app;
+
+    #[test]
+    fn test_shared_prefix() {
+        let line = "2024-05-01 ERROR Failed to start app nextcloud-aio: port 8080 is already in use";
+
+        assert!(!mentions_app(line, "nextcloud"));
+        assert!(mentions_app(line, "nextcloud-aio"));
+        assert!(mentions_app("Failed to start app nextcloud: exit code 1", "nextcloud"));
+        assert!(!mentions_app("Failed to pull n8nio/n8n-worker", "n8n"));
+    }
+
+    #[test]
+    fn test_store_id() {
+        assert!(mentions_app("Failed to start app whoami:private", "whoami:private"));
+        assert!(mentions_app("Failed to start app whoami", "whoami:private"));
+        assert!(!mentions_app("Failed to start app whoami:official", "whoami:private"));
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod test_prune_archives {
+    use std::fs::{self, File};
+    use std::num::NonZeroUsize;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use crate::utils::backups::{list_archives, prune_archives};
+
+    fn create_archive(folder: &Path, name: &str, age_in_days: u64) {
+        let path = folder.join(name);
+        fs::write(&path, "archive").unwrap();
+
+        let modified = SystemTime::now() - Duration::from_secs(age_in_days * 24 * 3600);
+        File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn test_keeps_newest_archives() {
+        let folder = tempfile::tempdir().unwrap();
+        create_archive(folder.path(), "whoami-old.tar.gz", 3);
+        create_archive(folder.path(), "whoami-new.tar.gz", 0);
+        create_archive(folder.path(), "whoami-mid.tar.gz", 1);
+        fs::write(folder.path().join("notes.txt"), "not an archive").unwrap();
+
+        let mut removed = prune_archives(folder.path(), NonZeroUsize::new(1).unwrap());
+        removed.sort();
+
+        assert_eq!(removed, vec!["whoami-mid.tar.gz", "whoami-old.tar.gz"]);
+        assert_eq!(
+            list_archives(folder.path())
+                .into_iter()
+                .map(|archive| archive.name)
+                .collect::<Vec<String>>(),
+            vec!["whoami-new.tar.gz"]
+        );
+        assert!(folder.path().join("notes.txt").exists());
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod test_context_store {
+    use crate::utils::context::{Context, ContextStore};
+
+    fn context(name: &str) -> Context {
+        Context {
+            name: name.to_string(),
+            api_url: format!("https://{}.example.com/api", name),
+            jwt_secret: Some("secret".to_string()),
+            token: None,
+            ca_cert: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtipi-cli").join("contexts.json");
+
+        assert!(ContextStore::load(&path).unwrap().contexts.is_empty());
+
+        let store = ContextStore {
+            current: Some("home".to_string()),
+            contexts: vec![context("home")],
+        };
+        store.save(&path).unwrap();
+
+        let loaded = ContextStore::load(&path).unwrap();
+        assert_eq!(loaded.current.as_deref(), Some("home"));
+        assert_eq!(loaded.contexts, vec![context("home")]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+            // A file left readable by others is replaced instead of written in place
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            store.save(&path).unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+            assert!(!path.with_extension("json.tmp").exists());
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let store = ContextStore {
+            current: Some("home".to_string()),
+            contexts: vec![context("home"), context("office")],
+        };
+
+        assert_eq!(store.resolve(None).unwrap().map(|c| c.name.as_str()), Some("home"));
+        assert_eq!(store.resolve(Some("office")).unwrap().map(|c| c.name.as_str()), Some("office"));
+        assert_eq!(store.resolve(Some("default")).unwrap(), None);
+        assert!(store.resolve(Some("missing")).is_err());
+        assert_eq!(ContextStore::default().resolve(None).unwrap(), None);
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test_dotenv {
+    use proptest::prelude::*;
+
+    use crate::utils::dotenv::{format_entry, parse, quote_value, EnvDocument};
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_compose_syntax() {
+        let input = "# comment\n\nexport A=1\n  B = two words  # inline comment\nC=a#b\nD='$A # kept'\nE=\"line\\nbreak \\\"quoted\\\"\"\nF=\nG='multi\nline'\r\n";
+
+        assert_eq!(
+            parse(input).unwrap(),
+            entries(&[
+                ("A", "1"),
+                ("B", "two words"),
+                ("C", "a#b"),
+                ("D", "$A # kept"),
+                ("E", "line\nbreak \"quoted\""),
+                ("F", ""),
+                ("G", "multi\nline"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_interpolation() {
+        let input = "A=one\nB=${A}-$A\nC=\"${MISSING:-default} $$A\"\nD='${A}'\nE=${A:+set}${MISSING+unset}\n";
+
+        assert_eq!(
+            parse(input).unwrap(),
+            entries(&[("A", "one"), ("B", "one-one"), ("C", "default $A"), ("D", "${A}"), ("E", "set")])
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_errors_and_keeps_going() {
+        let errors = parse("A=1\nB='unterminated\nC=\"ok\" trailing\nD=2\n").unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(|error| error.to_string()).collect::<Vec<String>>(),
+            vec![
+                "Line 2: unterminated single-quoted value",
+                "Line 3: unexpected characters after the closing quote"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quote_value() {
+        assert_eq!(quote_value("plain-value_1.2"), "plain-value_1.2");
+        assert_eq!(quote_value("pa ss#word$"), "'pa ss#word$'");
+        assert_eq!(quote_value("it's $5\n"), "\"it's $$5\\n\"");
+        assert_eq!(quote_value(""), "");
+    }
+
+    #[test]
+    fn test_document_keeps_layout() {
+        let input = "# Managed by hand\nB = 'two'  # why\n\nUNKNOWN=kept\nA=1\n";
+        let mut document = EnvDocument::parse(input);
+
+        document.set("B", "two");
+        assert_eq!(document.to_string(), input);
+
+        document.set("A", "new value");
+        document.set("C", "3");
+        assert_eq!(
+            document.to_string(),
+            "# Managed by hand\nB = 'two'  # why\n\nUNKNOWN=kept\nA='new value'\nC=3\n"
+        );
+    }
+
+    #[test]
+    fn test_document_set_and_remove_duplicates() {
+        let mut document = EnvDocument::parse("A=1\nB=2\nA=3\n");
+        assert_eq!(document.get("A"), Some("3"));
+
+        document.set("A", "1");
+        assert_eq!(document.to_string(), "A=1\nB=2\n");
+
+        document.remove("A");
+        assert_eq!(document.to_string(), "B=2\n");
+    }
+
+    #[test]
+    fn test_document_redacted_hides_multiline_values() {
+        let document = EnvDocument::parse("# db\nexport POSTGRES_PASSWORD='abc\ndef'\nPORT=80\n");
+        let hide = |key: &str| key.contains("PASSWORD").then(|| "<redacted>".to_string());
+
+        assert_eq!(document.redacted(hide).to_string(), "# db\nPOSTGRES_PASSWORD=<redacted>\nPORT=80\n");
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(pairs in prop::collection::vec(("[A-Za-z_][A-Za-z0-9_]{0,12}", any::<String>()), 0..8)) {
+            let content = pairs.iter().map(|(key, value)| format!("{}\n", format_entry(key, value))).collect::<String>();
+
+            prop_assert_eq!(parse(&content).unwrap(), pairs);
+        }
+
+        #[test]
+        fn test_parse_never_panics(input in any::<String>()) {
+            let _ = parse(&input);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod test_env_editing {
+    use crate::utils::env::{set_env_value, unset_env_value, validate_env_string};
+
+    #[test]
+    fn test_set_replaces_in_place() {
+        let env = "# comment\nA=1\nB=2\n";
+
+        assert_eq!(set_env_value(env, "A", "3"), "# comment\nA=3\nB=2\n");
+    }
+
+    #[test]
+    fn test_set_appends_missing_key() {
+        assert_eq!(set_env_value("A=1", "B", "2"), "A=1\nB=2\n");
+    }
+
+    #[test]
+    fn test_unset_keeps_other_lines() {
+        assert_eq!(unset_env_value("A=1\n# comment\nAB=2\n", "A"), "# comment\nAB=2\n");
+    }
+
+    #[test]
+    fn test_validate_reports_every_line() {
+        let errors = validate_env_string("A=1\nnot a variable\n\n# ok\n1B=2\n").unwrap_err();
+
+        assert_eq!(errors, vec!["Line 2: expected KEY=VALUE", "Line 5: '1B' is not a valid variable name"]);
+    }
+}
+
+#[cfg(test)]
+mod test_managed_values {
+    use std::collections::BTreeMap;
+
+    use crate::utils::dotenv::EnvDocument;
+    use crate::utils::env::apply_managed_values;
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_user_keys_are_kept_and_edits_reported() {
+        let mut document = EnvDocument::parse("DOMAIN=edited.com\nRUNTIPI_MEDIA_PATH=/mnt/media\nOLD=1\n");
+        let previous = pairs(&[("DOMAIN", "example.com"), ("OLD", "1")]).into_iter().collect::<BTreeMap<_, _>>();
+        let managed = pairs(&[("DOMAIN", "example.com"), ("LOG_LEVEL", "info"), ("LOG_LEVEL", "debug")]);
+        let mut warnings = vec![];
+
+        let values = apply_managed_values(&mut document, &managed, &previous, &mut warnings);
+
+        assert_eq!(
+            document.to_string(),
+            "DOMAIN=example.com\nRUNTIPI_MEDIA_PATH=/mnt/media\nLOG_LEVEL=debug\n"
+        );
+        assert_eq!(values.get("LOG_LEVEL").map(|v| v.as_str()), Some("debug"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("DOMAIN was edited in .env"));
+    }
+
+    #[test]
+    fn test_settings_changes_are_not_reported() {
+        let mut document = EnvDocument::parse("DOMAIN=example.com\n");
+        let previous = pairs(&[("DOMAIN", "example.com")]).into_iter().collect::<BTreeMap<_, _>>();
+        let mut warnings = vec![];
+
+        apply_managed_values(&mut document, &pairs(&[("DOMAIN", "tipi.lan")]), &previous, &mut warnings);
+
+        assert_eq!(document.to_string(), "DOMAIN=tipi.lan\n");
+        assert!(warnings.is_empty());
+    }
+}
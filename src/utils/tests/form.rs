@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test_validate_form_values {
+    use std::collections::BTreeMap;
+
+    use serde_json::{json, Value};
+
+    use crate::utils::form::{parse_key_value, validate_form_values};
+    use crate::utils::schemas::FormField;
+
+    fn fields() -> Vec<FormField> {
+        serde_json::from_value(json!([
+            { "type": "text", "label": "Username", "env_variable": "APP_USERNAME", "required": true, "min": 3 },
+            { "type": "number", "label": "Workers", "env_variable": "APP_WORKERS", "min": 1, "max": 8 },
+            { "type": "boolean", "label": "Debug", "env_variable": "APP_DEBUG" },
+            { "type": "random", "label": "Secret", "env_variable": "APP_SECRET", "required": true },
+            { "type": "text", "label": "Mode", "env_variable": "APP_MODE", "options": [{ "label": "Fast", "value": "fast" }] }
+        ]))
+        .unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, Value> {
+        pairs.iter().map(|(k, v)| (k.to_string(), Value::String(v.to_string()))).collect()
+    }
+
+    #[test]
+    fn test_valid_values_are_typed() {
+        let form = validate_form_values(
+            &fields(),
+            &values(&[("APP_USERNAME", "admin"), ("APP_WORKERS", "4"), ("APP_DEBUG", "true")]),
+        )
+        .unwrap();
+
+        assert_eq!(form["APP_USERNAME"], json!("admin"));
+        assert_eq!(form["APP_WORKERS"], json!(4));
+        assert_eq!(form["APP_DEBUG"], json!(true));
+    }
+
+    #[test]
+    fn test_collects_every_error() {
+        let errors = validate_form_values(&fields(), &values(&[("APP_WORKERS", "12"), ("APP_MODE", "slow"), ("APP_UNKNOWN", "x")])).unwrap_err();
+
+        assert_eq!(errors.len(), 4);
+        assert!(errors.iter().any(|e| e.contains("Unknown field APP_UNKNOWN")));
+        assert!(errors.iter().any(|e| e.contains("APP_USERNAME") && e.contains("required")));
+        assert!(errors.iter().any(|e| e.contains("between 1 and 8")));
+        assert!(errors.iter().any(|e| e.contains("one of: fast")));
+    }
+
+    #[test]
+    fn test_reserved_keys_are_passed_through() {
+        let form = validate_form_values(
+            &fields(),
+            &values(&[("APP_USERNAME", "admin"), ("exposed", "true"), ("domain", "app.example.com")]),
+        )
+        .unwrap();
+
+        assert_eq!(form["exposed"], json!(true));
+        assert_eq!(form["domain"], json!("app.example.com"));
+    }
+
+    #[test]
+    fn test_parse_key_value() {
+        assert_eq!(parse_key_value("KEY=a=b").unwrap(), ("KEY".to_string(), "a=b".to_string()));
+        assert!(parse_key_value("KEY").is_err());
+        assert!(parse_key_value("=value").is_err());
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod test_repos {
+    use std::fs;
+
+    use crate::utils::repos::{get_configured_repos, is_valid_repo_id, repo_id_from_url, save_configured_repos};
+    use crate::utils::schemas::AppStoreRepo;
+
+    #[test]
+    fn test_repo_id_from_url() {
+        assert_eq!(repo_id_from_url("https://github.com/runtipi/runtipi-appstore"), "runtipi-appstore");
+        assert_eq!(repo_id_from_url("https://github.com/me/My.Store.git/"), "my-store");
+        assert_eq!(repo_id_from_url("git@github.com:me/store.git"), "store");
+        assert!(is_valid_repo_id("my_store-2"));
+        assert!(!is_valid_repo_id("../store"));
+    }
+
+    #[test]
+    fn test_save_keeps_other_settings() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("state")).unwrap();
+        fs::write(
+            root.path().join("state/settings.json"),
+            r#"{"port": 8080, "guestDashboard": true, "appsRepoId": "official"}"#,
+        )
+        .unwrap();
+
+        let repo = AppStoreRepo {
+            id: "private".to_string(),
+            url: "https://example.com/private.git".to_string(),
+            branch: None,
+        };
+        save_configured_repos(root.path(), std::slice::from_ref(&repo)).unwrap();
+
+        assert_eq!(get_configured_repos(root.path()).unwrap(), vec![repo]);
+
+        let settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(root.path().join("state/settings.json")).unwrap()).unwrap();
+        assert_eq!(settings["port"], 8080);
+        assert_eq!(settings["guestDashboard"], true);
+        assert_eq!(
+            settings.as_object().unwrap().keys().collect::<Vec<&String>>(),
+            vec!["port", "guestDashboard", "appsRepoId", "appStores"]
+        );
+
+        save_configured_repos(root.path(), &[]).unwrap();
+        assert!(get_configured_repos(root.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod test_settings {
+    use crate::utils::settings::{validate_settings, Severity};
+
+    #[test]
+    fn test_valid_settings() {
+        let content = r#"{ "port": 8080, "sslPort": "8443", "domain": "tipi.example.com", "logLevel": "debug", "guestDashboard": true }"#;
+
+        assert!(validate_settings(content).is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_problem_with_its_position() {
+        let content =
+            "{\n  \"doman\": \"tipi.example.com\",\n  \"port\": 70000,\n  \"localDomain\": \"https://tipi.lan\",\n  \"logLevel\": \"loud\"\n}";
+
+        let problems = validate_settings(content)
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(problems.len(), 4);
+        assert_eq!(problems[0], "line 2, column 3: unknown key \"doman\", did you mean \"domain\"?");
+        assert!(problems[1].starts_with("line 3, column 3: invalid \"port\""));
+        assert!(problems[2].starts_with("line 4, column 3: invalid \"localDomain\""));
+        assert!(problems[3].starts_with("line 5, column 3: invalid \"logLevel\""));
+    }
+
+    #[test]
+    fn test_ip_addresses() {
+        let problems = validate_settings(r#"{ "listenIp": "192.0.2.1", "internalIp": "not-an-ip" }"#);
+
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].severity, Severity::Warning);
+        assert!(problems[0].message.contains("192.0.2.1 is not an address of this machine"));
+        assert_eq!(problems[1].severity, Severity::Error);
+        assert!(problems[1].message.starts_with("invalid \"internalIp\""));
+
+        let problems = validate_settings(r#"{ "listenIp": "192.0.2" }"#);
+        assert_eq!(problems[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let problems = validate_settings("{\n  \"port\": 80,\n}");
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, Severity::Error);
+        assert_eq!((problems[0].line, problems[0].column), (3, 1));
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod test_store {
+    use std::fs;
+    use std::path::Path;
+
+    use crate::utils::apps::InstalledApp;
+    use crate::utils::store::{find_store_app, get_store_apps, levenshtein, search_store_apps};
+
+    fn write_config(root: &Path, store: &str, name: &str, short_desc: &str, categories: &[&str]) {
+        let app_folder = root.join("repos").join(store).join("apps").join(name);
+        fs::create_dir_all(&app_folder).unwrap();
+
+        let config = serde_json::json!({
+            "id": name,
+            "name": name,
+            "version": "1.0.0",
+            "short_desc": short_desc,
+            "categories": categories,
+        });
+        fs::write(app_folder.join("config.json"), config.to_string()).unwrap();
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        write_config(root.path(), "official", "nextcloud", "Your files in the cloud", &["data"]);
+        write_config(root.path(), "official", "jellyfin", "Media server", &["media"]);
+        write_config(root.path(), "official", "owncloud", "File hosting", &["data"]);
+        write_config(root.path(), "private", "nextcloud", "Patched nextcloud", &["data"]);
+        fs::create_dir_all(root.path().join("repos/official/apps/broken")).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_get_store_apps() {
+        let root = fixture();
+
+        let ids = get_store_apps(root.path()).unwrap().iter().map(|app| app.id()).collect::<Vec<String>>();
+
+        assert_eq!(
+            ids,
+            vec!["jellyfin:official", "nextcloud:official", "owncloud:official", "nextcloud:private"]
+        );
+    }
+
+    #[test]
+    fn test_search_ranks_name_matches_first() {
+        let root = fixture();
+        let apps = get_store_apps(root.path()).unwrap();
+
+        let ids = search_store_apps(&apps, "cloud").iter().map(|app| app.id()).collect::<Vec<String>>();
+        assert_eq!(ids, vec!["nextcloud:official", "owncloud:official", "nextcloud:private"]);
+
+        let ids = search_store_apps(&apps, "nxtcld").iter().map(|app| app.id()).collect::<Vec<String>>();
+        assert_eq!(ids, vec!["nextcloud:official", "nextcloud:private"]);
+
+        let ids = search_store_apps(&apps, "jelyfin").iter().map(|app| app.id()).collect::<Vec<String>>();
+        assert_eq!(ids, vec!["jellyfin:official"]);
+
+        let ids = search_store_apps(&apps, "MEDIA").iter().map(|app| app.id()).collect::<Vec<String>>();
+        assert_eq!(ids, vec!["jellyfin:official"]);
+    }
+
+    #[test]
+    fn test_find_store_app() {
+        let root = fixture();
+        let apps = get_store_apps(root.path()).unwrap();
+
+        assert_eq!(find_store_app(&apps, "nextcloud:private", None).unwrap().store, "private");
+        assert_eq!(find_store_app(&apps, "nextcloud", Some("official")).unwrap().store, "official");
+        assert!(find_store_app(&apps, "nextcloud", None).is_err());
+        assert!(find_store_app(&apps, "missing", None).is_err());
+    }
+
+    #[test]
+    fn test_is_installed() {
+        let root = fixture();
+        let apps = get_store_apps(root.path()).unwrap();
+        let nextcloud = find_store_app(&apps, "nextcloud:private", None).unwrap();
+
+        let legacy = InstalledApp {
+            id: "nextcloud".to_string(),
+            name: "nextcloud".to_string(),
+            store: None,
+            path: root.path().join("apps/nextcloud"),
+        };
+        let other_store = InstalledApp {
+            id: "nextcloud:official".to_string(),
+            name: "nextcloud".to_string(),
+            store: Some("official".to_string()),
+            path: root.path().join("apps/official/nextcloud"),
+        };
+
+        assert!(nextcloud.is_installed(&[legacy]));
+        assert!(!nextcloud.is_installed(&[other_store]));
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test_docker_exec_transport {
+    use reqwest::{Method, StatusCode};
+    use serde_json::json;
+
+    use crate::utils::transport::{curl_config, curl_exit_error, parse_curl_output, ApiRequest, RawResponse, TransportError};
+
+    #[test]
+    fn test_curl_config_escapes_values() {
+        let body = json!({ "name": "say \"hi\"", "path": "C:\\data" });
+        let request = ApiRequest {
+            method: Method::POST,
+            path: "app-lifecycle/whoami/install",
+            token: "abc",
+            body: Some(&body),
+        };
+
+        let config = curl_config(&request);
+
+        assert!(config.contains("url = \"http://localhost:3000/api/app-lifecycle/whoami/install\"\n"));
+        assert!(config.contains("request = \"POST\"\n"));
+        assert!(config.contains("header = \"Authorization: Bearer abc\"\n"));
+        assert!(config.contains(r#"data-binary = "{\"name\":\"say \\\"hi\\\"\",\"path\":\"C:\\\\data\"}""#));
+    }
+
+    #[test]
+    fn test_curl_config_without_body() {
+        let request = ApiRequest {
+            method: Method::GET,
+            path: "apps/installed",
+            token: "abc",
+            body: None,
+        };
+
+        let config = curl_config(&request);
+
+        assert!(!config.contains("data-binary"));
+        assert!(!config.contains("Content-Type"));
+    }
+
+    #[test]
+    fn test_parse_curl_output() {
+        assert_eq!(
+            parse_curl_output("{\"message\":\"ok\"}\n200"),
+            Some(RawResponse {
+                status: StatusCode::OK,
+                body: "{\"message\":\"ok\"}".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_curl_output("line one\nline two\n404").map(|response| response.body),
+            Some("line one\nline two".to_string())
+        );
+        assert_eq!(parse_curl_output("no status"), None);
+    }
+
+    #[test]
+    fn test_curl_exit_error() {
+        let error = |code: Option<i32>| curl_exit_error(code, "error".to_string());
+
+        for code in [1, 6, 7, 125, 126, 127] {
+            assert_eq!(error(Some(code)), TransportError::Unreachable("error".to_string()));
+        }
+        for code in [18, 28, 52, 56] {
+            assert_eq!(error(Some(code)), TransportError::Failed("error".to_string()));
+        }
+        assert_eq!(error(None), TransportError::Failed("error".to_string()));
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod test_validation {
+    use crate::utils::validation::{validate_domain, validate_env_value, validate_port};
+
+    #[test]
+    fn test_validate_port() {
+        assert!(validate_port("443").is_ok());
+        assert!(validate_port("0").is_err());
+        assert!(validate_port("65536").is_err());
+        assert!(validate_port("http").is_err());
+    }
+
+    #[test]
+    fn test_validate_domain() {
+        assert!(validate_domain("tipi.example.com").is_ok());
+        assert!(validate_domain("localhost").is_ok());
+        assert!(validate_domain("https://tipi.example.com").is_err());
+        assert!(validate_domain("-tipi.lan").is_err());
+        assert!(validate_domain("tipi..lan").is_err());
+    }
+
+    #[test]
+    fn test_validate_env_value() {
+        assert!(validate_env_value("NGINX_PORT", "8080").is_ok());
+        assert!(validate_env_value("INTERNAL_IP", "192.168.1.300").is_err());
+        assert!(validate_env_value("LOG_LEVEL", "verbose").is_err());
+        assert!(validate_env_value("RUNTIPI_API_URL", "tipi.lan/api").is_err());
+        assert!(validate_env_value("RUNTIPI_MEDIA_PATH", "anything").is_ok());
+    }
+}
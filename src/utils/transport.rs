@@ -0,0 +1,187 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::{blocking::Client, Method, StatusCode};
+use serde_json::Value;
+
+use crate::utils::constants::{DASHBOARD_CONTAINER, DASHBOARD_PORT, MAIN_NETWORK};
+use crate::utils::docker::get_container_ip;
+
+/// Unroutable container addresses (eg: Docker Desktop) would otherwise hang until the OS gives up
+const DIRECT_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/**
+* Exit codes of `docker exec` and curl meaning the request never left the container: docker errors (1 when the
+* container is missing or stopped, 125 to 127 when curl can't be run) and curl failing to resolve (6) or connect (7)
+* to the dashboard. Any other failure, eg: an empty reply (52) or a timeout (28), may happen after the dashboard
+* received the request.
+*/
+const NOT_SENT_EXIT_CODES: &[i32] = &[1, 6, 7, 125, 126, 127];
+
+pub struct ApiRequest<'a> {
+    pub method: Method,
+    /// The path after `/api/`, eg: `apps/installed`
+    pub path: &'a str,
+    pub token: &'a str,
+    pub body: Option<&'a Value>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RawResponse {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TransportError {
+    /// Nothing reached the dashboard, the next transport can be tried
+    Unreachable(String),
+    /// The request may have reached the dashboard, it must not be sent again
+    Failed(String),
+}
+
+/// A way to reach the dashboard API
+pub enum Transport {
+    /// Through the reverse proxy, eg: http://192.168.1.10:80/api
+    Proxy { url: String, client: Client },
+    /// Straight to the dashboard container on the docker network, the address is resolved on first use
+    ContainerIp { client: Client, url: OnceLock<Result<String, String>> },
+    /// `curl` inside the dashboard container, for hosts where container addresses are not routable
+    DockerExec { timeout: Duration },
+}
+
+impl Transport {
+    pub fn container_ip(timeout: Duration) -> Result<Transport, String> {
+        let client = Client::builder()
+            .user_agent("reqwest")
+            .connect_timeout(DIRECT_CONNECT_TIMEOUT)
+            .timeout(timeout)
+            .build()
+            .map_err(|err| format!("Error creating client: {}", err))?;
+
+        Ok(Transport::ContainerIp {
+            client,
+            url: OnceLock::new(),
+        })
+    }
+
+    pub fn execute(&self, request: &ApiRequest) -> Result<RawResponse, TransportError> {
+        match self {
+            Transport::Proxy { url, client } => match http_execute(client, url, request)? {
+                // The proxy could not reach the dashboard, going around it may still work
+                response if response.status == StatusCode::BAD_GATEWAY => Err(TransportError::Unreachable(format!("{} (bad gateway)", url))),
+                response => Ok(response),
+            },
+            Transport::ContainerIp { client, url } => {
+                let url = url.get_or_init(|| {
+                    get_container_ip(DASHBOARD_CONTAINER, MAIN_NETWORK)
+                        .map(|ip| format!("http://{}:{}/api", ip, DASHBOARD_PORT))
+                        .map_err(|err| err.to_string())
+                });
+
+                match url {
+                    Ok(url) => http_execute(client, url, request),
+                    Err(err) => Err(TransportError::Unreachable(err.clone())),
+                }
+            }
+            Transport::DockerExec { timeout } => docker_exec_execute(request, *timeout),
+        }
+    }
+}
+
+fn http_execute(client: &Client, base_url: &str, request: &ApiRequest) -> Result<RawResponse, TransportError> {
+    let mut builder = client
+        .request(request.method.clone(), format!("{}/{}", base_url, request.path))
+        .header("Authorization", format!("Bearer {}", request.token))
+        .header("Accept", "application/json");
+
+    if let Some(body) = request.body {
+        builder = builder.header("Content-Type", "application/json").body(body.to_string());
+    }
+
+    let response = match builder.send() {
+        Ok(response) => response,
+        Err(err) if err.is_connect() => return Err(TransportError::Unreachable(base_url.to_string())),
+        Err(err) => return Err(TransportError::Failed(err.to_string())),
+    };
+
+    let status = response.status();
+    let body = response.text().map_err(|err| TransportError::Failed(err.to_string()))?;
+
+    Ok(RawResponse { status, body })
+}
+
+fn quote_curl_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The curl config, sent on stdin so the token does not show up in the process list
+pub fn curl_config(request: &ApiRequest) -> String {
+    let mut lines = vec![
+        format!(
+            "url = {}",
+            quote_curl_value(&format!("http://localhost:{}/api/{}", DASHBOARD_PORT, request.path))
+        ),
+        format!("request = {}", quote_curl_value(request.method.as_str())),
+        format!("header = {}", quote_curl_value(&format!("Authorization: Bearer {}", request.token))),
+        format!("header = {}", quote_curl_value("Accept: application/json")),
+    ];
+
+    if let Some(body) = request.body {
+        lines.push(format!("header = {}", quote_curl_value("Content-Type: application/json")));
+        lines.push(format!("data-binary = {}", quote_curl_value(&body.to_string())));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// curl prints the body followed by the status code on its own line, see `--write-out`
+pub fn parse_curl_output(output: &str) -> Option<RawResponse> {
+    let (body, status) = output.rsplit_once('\n')?;
+    let status = StatusCode::from_u16(status.trim().parse().ok()?).ok()?;
+
+    Some(RawResponse {
+        status,
+        body: body.to_string(),
+    })
+}
+
+/// Only a failure that happened before the request was sent can be retried, see `NOT_SENT_EXIT_CODES`
+pub fn curl_exit_error(code: Option<i32>, message: String) -> TransportError {
+    match code {
+        Some(code) if NOT_SENT_EXIT_CODES.contains(&code) => TransportError::Unreachable(message),
+        _ => TransportError::Failed(message),
+    }
+}
+
+fn docker_exec_execute(request: &ApiRequest, timeout: Duration) -> Result<RawResponse, TransportError> {
+    let description = format!("docker exec in container {}", DASHBOARD_CONTAINER);
+    let unreachable = |err: String| TransportError::Unreachable(format!("{} ({})", description, err));
+
+    let mut child = Command::new("docker")
+        .args(["exec", "--interactive", DASHBOARD_CONTAINER])
+        .args(["curl", "--silent", "--show-error", "--config", "-", "--write-out", "\n%{http_code}"])
+        .arg("--max-time")
+        .arg(timeout.as_secs_f64().to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| unreachable(err.to_string()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(curl_config(request).as_bytes())
+            .map_err(|err| unreachable(err.to_string()))?;
+    }
+
+    let output = child.wait_with_output().map_err(|err| unreachable(err.to_string()))?;
+
+    if !output.status.success() {
+        let message = format!("{} ({})", description, String::from_utf8_lossy(&output.stderr).trim());
+        return Err(curl_exit_error(output.status.code(), message));
+    }
+
+    parse_curl_output(&String::from_utf8_lossy(&output.stdout)).ok_or(TransportError::Failed("Unable to read the response of curl".to_string()))
+}
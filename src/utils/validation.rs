@@ -0,0 +1,63 @@
+use std::net::IpAddr;
+use std::path::Path;
+
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+pub fn validate_port(value: &str) -> Result<(), String> {
+    match value.parse::<u16>() {
+        Ok(port) if port > 0 => Ok(()),
+        _ => Err(format!("'{}' is not a valid port, use a number between 1 and 65535", value)),
+    }
+}
+
+pub fn validate_ip(value: &str) -> Result<(), String> {
+    value
+        .parse::<IpAddr>()
+        .map(|_| ())
+        .map_err(|_| format!("'{}' is not a valid IP address", value))
+}
+
+/// A host name made of labels of letters, digits and `-`, eg: `tipi.lan`
+pub fn validate_domain(value: &str) -> Result<(), String> {
+    let is_valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+
+    if value.len() <= 253 && value.split('.').all(is_valid_label) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid domain, eg: tipi.example.com", value))
+    }
+}
+
+pub fn validate_log_level(value: &str) -> Result<(), String> {
+    if LOG_LEVELS.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid log level, use one of: {}", value, LOG_LEVELS.join(", ")))
+    }
+}
+
+/// Check the value of a variable the CLI knows about, any value is accepted for the others
+pub fn validate_env_value(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "NGINX_PORT" | "NGINX_PORT_SSL" | "POSTGRES_PORT" => validate_port(value),
+        "INTERNAL_IP" => validate_ip(value),
+        "DOMAIN" | "LOCAL_DOMAIN" => validate_domain(value),
+        "LOG_LEVEL" => validate_log_level(value),
+        "RUNTIPI_API_CONNECT_TIMEOUT" | "RUNTIPI_API_TIMEOUT" | "RUNTIPI_API_RETRIES" => {
+            value.parse::<u64>().map(|_| ()).map_err(|_| format!("'{}' is not a valid number", value))
+        }
+        "RUNTIPI_API_URL" | "RUNTIPI_FORWARD_AUTH_URL" if !value.starts_with("http://") && !value.starts_with("https://") => {
+            Err(format!("'{}' is not a valid url, it must start with http:// or https://", value))
+        }
+        "RUNTIPI_APP_DATA_PATH" if !Path::new(value).is_absolute() || !Path::new(value).exists() => {
+            Err(format!("'{}' must be an absolute path to an existing folder", value))
+        }
+        _ => Ok(()),
+    }
+}